@@ -39,7 +39,7 @@ class FlightServer(pa.flight.FlightServerBase):
         if action.type == "ResetNode":
             self.do_reset_node()
         elif action.type == "FlushNode":
-            self.do_flush_node()
+            self.do_flush_node(action)
         elif action.type == "IngestDataParquet":
             self.do_ingest_data_parquet(action)
         elif action.type == "IngestDataOrc":
@@ -60,7 +60,11 @@ class FlightServer(pa.flight.FlightServerBase):
             for minio_object in self.minio_client.list_objects("comparison", recursive=True):
                 self.minio_client.remove_object("comparison", minio_object.object_name)
 
-    def do_flush_node(self):
+    def do_flush_node(self, action: Action):
+        # The files are flushed to the directory of the table named in the body, if any.
+        table_name = action.body.to_pybytes().decode()
+        table_path = f"tables/{table_name}" if table_name else "tables"
+
         total_size_flushed = 0
 
         for file in os.listdir("data"):
@@ -69,7 +73,7 @@ class FlightServer(pa.flight.FlightServerBase):
             total_size_flushed += os.path.getsize(file_path)
 
             if total_size_flushed < self.bandwidth_limit:
-                self.minio_client.fput_object("comparison", f"tables/{file}", file_path)
+                self.minio_client.fput_object("comparison", f"{table_path}/{file}", file_path)
                 os.remove(file_path)
             else:
                 break
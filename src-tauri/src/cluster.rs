@@ -0,0 +1,615 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use arrow_flight::flight_service_client::FlightServiceClient;
+use bollard::models::{
+    ContainerCreateBody, HostConfig, NetworkCreateRequest, PortBinding, RestartPolicy,
+    RestartPolicyNameEnum,
+};
+use bollard::query_parameters::{
+    CreateContainerOptionsBuilder, CreateImageOptionsBuilder, InspectContainerOptions,
+    InspectNetworkOptions, RemoveContainerOptionsBuilder, RestartContainerOptionsBuilder,
+    StartContainerOptions, WaitContainerOptions,
+};
+use bollard::Docker;
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use tauri::AppHandle;
+use tokio::time;
+use url::Url;
+
+use crate::error::DemoError;
+use crate::events::EventEmitter;
+use crate::topology::{NodeType, ServerMode, Topology};
+
+/// Prefix of the Docker resources created for the cluster. It matches the project name used in the
+/// README so volumes created by either `docker-compose` or the demo are monitored the same way.
+const PROJECT_NAME: &str = "modelardb-cluster";
+
+/// How long to wait for a container to become ready before giving up.
+const READINESS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long to wait between each readiness check.
+const READINESS_INTERVAL: Duration = Duration::from_millis(500);
+
+const MINIO_IMAGE: &str = "minio/minio:latest";
+const MINIO_CLIENT_IMAGE: &str = "minio/mc:latest";
+const MODELARDB_IMAGE: &str = "cgodiksen/modelardb:latest";
+
+/// The image of the comparison nodes, which is not published to a registry and must be built from
+/// the `comparison-system` directory in the repository before the cluster is started.
+const COMPARISON_IMAGE: &str = "comparison-system:latest";
+
+/// How to determine that a container is ready after it has been started.
+#[derive(Clone)]
+enum Readiness {
+    /// The container has run to completion.
+    Exited,
+    /// An HTTP GET request to the URL succeeds.
+    Http(String),
+    /// An Apache Arrow Flight client can connect to the URL.
+    Flight(String),
+}
+
+/// A container in the cluster, corresponding to a service in `docker-compose-cluster.yml`.
+#[derive(Clone)]
+struct ContainerSpec {
+    name: String,
+    image: String,
+    cmd: Option<Vec<String>>,
+    entrypoint: Option<Vec<String>>,
+    env: Vec<String>,
+    ports: Vec<u16>,
+    volume: Option<(String, String)>,
+    restart_on_failure: bool,
+    readiness: Readiness,
+    /// The directory in the repository the image is built from if it cannot be pulled.
+    build_context: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct ClusterProgress {
+    container: String,
+    status: String,
+}
+
+/// Create and start the containers in the cluster described by `topology` in dependency order and
+/// wait for each of them to be ready. Containers that are already running are left as they are.
+/// Returns [`DemoError::Docker`] before any container is started if an image that must be built
+/// from the repository does not exist.
+pub(super) async fn start_cluster(app: &AppHandle, topology: &Topology) -> Result<(), DemoError> {
+    let docker = connect()?;
+    let specs = container_specs(topology)?;
+
+    check_built_images(&docker, &specs).await?;
+    ensure_network(&docker).await?;
+
+    for spec in specs {
+        let running = docker
+            .inspect_container(&spec.name, None::<InspectContainerOptions>)
+            .await
+            .ok()
+            .and_then(|container| container.state)
+            .and_then(|state| state.running)
+            .unwrap_or(false);
+
+        if running && !matches!(spec.readiness, Readiness::Exited) {
+            emit_progress(app, &spec.name, "ready");
+            continue;
+        }
+
+        create_container_if_missing(app, &docker, &spec).await?;
+
+        emit_progress(app, &spec.name, "starting");
+        docker
+            .start_container(&spec.name, None::<StartContainerOptions>)
+            .await
+            .map_err(|error| {
+                DemoError::Docker(format!("Could not start {}: {error}", spec.name))
+            })?;
+
+        wait_until_ready(&docker, &spec).await?;
+        emit_progress(app, &spec.name, "ready");
+    }
+
+    Ok(())
+}
+
+/// Stop and remove the containers in the cluster described by `topology` in reverse dependency
+/// order. The volumes are kept so the data is available if the cluster is started again.
+pub(super) async fn stop_cluster(app: &AppHandle, topology: &Topology) -> Result<(), DemoError> {
+    let docker = connect()?;
+
+    for spec in container_specs(topology)?.iter().rev() {
+        emit_progress(app, &spec.name, "stopping");
+
+        let options = RemoveContainerOptionsBuilder::new().force(true).build();
+
+        // The container may not exist if the cluster was only partially started.
+        if docker
+            .remove_container(&spec.name, Some(options))
+            .await
+            .is_ok()
+        {
+            emit_progress(app, &spec.name, "removed");
+        }
+    }
+
+    let network_name = network_name();
+    if docker
+        .inspect_network(&network_name, None::<InspectNetworkOptions>)
+        .await
+        .is_ok()
+    {
+        docker
+            .remove_network(&network_name)
+            .await
+            .map_err(|error| {
+                DemoError::Docker(format!("Could not remove {network_name}: {error}"))
+            })?;
+    }
+
+    Ok(())
+}
+
+/// Restart the container of the node with `url` and wait for it to be ready.
+pub(super) async fn restart_node(
+    app: &AppHandle,
+    topology: &Topology,
+    url: &str,
+) -> Result<(), DemoError> {
+    let docker = connect()?;
+    let spec = container_spec_for_url(topology, url)?;
+
+    emit_progress(app, &spec.name, "restarting");
+
+    let options = RestartContainerOptionsBuilder::new().t(10).build();
+    docker
+        .restart_container(&spec.name, Some(options))
+        .await
+        .map_err(|error| DemoError::Docker(format!("Could not restart {}: {error}", spec.name)))?;
+
+    wait_until_ready(&docker, &spec).await?;
+    emit_progress(app, &spec.name, "ready");
+
+    Ok(())
+}
+
+/// Pause the container of the node with `url` if `paused` is true and unpause it otherwise.
+pub(super) async fn pause_node(
+    topology: &Topology,
+    url: &str,
+    paused: bool,
+) -> Result<(), DemoError> {
+    let docker = connect()?;
+    let spec = container_spec_for_url(topology, url)?;
+
+    let result = if paused {
+        docker.pause_container(&spec.name).await
+    } else {
+        docker.unpause_container(&spec.name).await
+    };
+
+    result.map_err(|error| {
+        let action = if paused { "pause" } else { "unpause" };
+        DemoError::Docker(format!("Could not {action} {}: {error}", spec.name))
+    })
+}
+
+/// Return the container in the cluster described by `topology` that exposes the port in `url`.
+fn container_spec_for_url(topology: &Topology, url: &str) -> Result<ContainerSpec, DemoError> {
+    let port = Url::parse(url)
+        .ok()
+        .and_then(|url| url.port())
+        .ok_or_else(|| {
+            DemoError::Configuration(format!("The URL {url} does not contain a port."))
+        })?;
+
+    container_specs(topology)?
+        .into_iter()
+        .find(|spec| spec.ports.contains(&port))
+        .ok_or_else(|| {
+            DemoError::Configuration(format!("No container in the cluster exposes port {port}."))
+        })
+}
+
+fn connect() -> Result<Docker, DemoError> {
+    Docker::connect_with_local_defaults()
+        .map_err(|error| DemoError::Connection(format!("Could not connect to Docker: {error}")))
+}
+
+fn network_name() -> String {
+    format!("{PROJECT_NAME}_default")
+}
+
+fn emit_progress(app: &AppHandle, container: &str, status: &str) {
+    app.emit_event(
+        "cluster-progress",
+        ClusterProgress {
+            container: container.to_owned(),
+            status: status.to_owned(),
+        },
+    );
+}
+
+/// Check that the images in `specs` that are built from the repository exist, as they cannot be
+/// pulled when their containers are created.
+async fn check_built_images(docker: &Docker, specs: &[ContainerSpec]) -> Result<(), DemoError> {
+    for spec in specs {
+        if let Some(build_context) = &spec.build_context {
+            if docker.inspect_image(&spec.image).await.is_err() {
+                return Err(DemoError::Docker(format!(
+                    "The {} image does not exist. Build it by running `docker build -t {} \
+                     {build_context}` in the root of the repository.",
+                    spec.image, spec.image
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Create the network the containers use to reach each other by name if it does not exist.
+async fn ensure_network(docker: &Docker) -> Result<(), DemoError> {
+    let network_name = network_name();
+
+    if docker
+        .inspect_network(&network_name, None::<InspectNetworkOptions>)
+        .await
+        .is_err()
+    {
+        let request = NetworkCreateRequest {
+            name: network_name.clone(),
+            ..Default::default()
+        };
+
+        docker.create_network(request).await.map_err(|error| {
+            DemoError::Docker(format!("Could not create {network_name}: {error}"))
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Create the container described by `spec` if it does not exist, pulling its image if necessary.
+async fn create_container_if_missing(
+    app: &AppHandle,
+    docker: &Docker,
+    spec: &ContainerSpec,
+) -> Result<(), DemoError> {
+    if docker
+        .inspect_container(&spec.name, None::<InspectContainerOptions>)
+        .await
+        .is_ok()
+    {
+        return Ok(());
+    }
+
+    if docker.inspect_image(&spec.image).await.is_err() {
+        emit_progress(app, &spec.name, "pulling");
+
+        let options = CreateImageOptionsBuilder::new()
+            .from_image(&spec.image)
+            .build();
+
+        docker
+            .create_image(Some(options), None, None)
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|error| {
+                DemoError::Docker(format!("Could not pull {}: {error}", spec.image))
+            })?;
+    }
+
+    emit_progress(app, &spec.name, "creating");
+
+    let exposed_ports = spec
+        .ports
+        .iter()
+        .map(|port| (format!("{port}/tcp"), HashMap::new()))
+        .collect();
+
+    let port_bindings = spec
+        .ports
+        .iter()
+        .map(|port| {
+            let binding = PortBinding {
+                host_ip: None,
+                host_port: Some(port.to_string()),
+            };
+
+            (format!("{port}/tcp"), Some(vec![binding]))
+        })
+        .collect();
+
+    let binds = spec
+        .volume
+        .as_ref()
+        .map(|(volume_name, container_path)| vec![format!("{volume_name}:{container_path}")]);
+
+    let restart_policy = spec.restart_on_failure.then(|| RestartPolicy {
+        name: Some(RestartPolicyNameEnum::ON_FAILURE),
+        maximum_retry_count: Some(10),
+    });
+
+    let body = ContainerCreateBody {
+        image: Some(spec.image.clone()),
+        cmd: spec.cmd.clone(),
+        entrypoint: spec.entrypoint.clone(),
+        env: Some(spec.env.clone()),
+        exposed_ports: Some(exposed_ports),
+        host_config: Some(HostConfig {
+            binds,
+            network_mode: Some(network_name()),
+            port_bindings: Some(port_bindings),
+            restart_policy,
+            extra_hosts: Some(vec!["host.docker.internal:host-gateway".to_owned()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let options = CreateContainerOptionsBuilder::new()
+        .name(&spec.name)
+        .build();
+
+    docker
+        .create_container(Some(options), body)
+        .await
+        .map_err(|error| DemoError::Docker(format!("Could not create {}: {error}", spec.name)))?;
+
+    Ok(())
+}
+
+/// Wait until the container described by `spec` is ready or [`READINESS_TIMEOUT`] has passed.
+async fn wait_until_ready(docker: &Docker, spec: &ContainerSpec) -> Result<(), DemoError> {
+    let start = Instant::now();
+
+    loop {
+        let ready = match &spec.readiness {
+            Readiness::Exited => {
+                return docker
+                    .wait_container(&spec.name, None::<WaitContainerOptions>)
+                    .try_collect::<Vec<_>>()
+                    .await
+                    .map(|_| ())
+                    .map_err(|error| {
+                        DemoError::Docker(format!("{} did not complete: {error}", spec.name))
+                    });
+            }
+            Readiness::Http(url) => reqwest::get(url)
+                .await
+                .is_ok_and(|response| response.status().is_success()),
+            Readiness::Flight(url) => FlightServiceClient::connect(url.clone()).await.is_ok(),
+        };
+
+        if ready {
+            return Ok(());
+        }
+
+        if start.elapsed() > READINESS_TIMEOUT {
+            return Err(DemoError::Docker(format!(
+                "{} did not become ready in time.",
+                spec.name
+            )));
+        }
+
+        time::sleep(READINESS_INTERVAL).await;
+    }
+}
+
+/// Return the containers in the cluster described by `topology` in the order they must be started.
+/// The `python-scripts` service in `docker-compose-cluster.yml` is not included, as it only runs
+/// the example scripts and is not used by the demo.
+fn container_specs(topology: &Topology) -> Result<Vec<ContainerSpec>, DemoError> {
+    let port = |url: &str| {
+        Url::parse(url)
+            .ok()
+            .and_then(|url| url.port())
+            .ok_or_else(|| {
+                DemoError::Configuration(format!("The URL {url} does not contain a port."))
+            })
+    };
+
+    let aws_env = vec![
+        "AWS_ACCESS_KEY_ID=minioadmin".to_owned(),
+        "AWS_SECRET_ACCESS_KEY=minioadmin".to_owned(),
+        "AWS_DEFAULT_REGION=eu-central-1".to_owned(),
+        "AWS_ENDPOINT=http://minio-server:9000".to_owned(),
+        "AWS_ALLOW_HTTP=true".to_owned(),
+    ];
+
+    let modelardb_env = |port: u16| {
+        let mut env = aws_env.clone();
+        env.extend([
+            "MODELARDBD_IP_ADDRESS=host.docker.internal".to_owned(),
+            "MODELARDBD_RETENTION_PERIOD_IN_SECONDS=0".to_owned(),
+            format!("MODELARDBD_PORT={port}"),
+        ]);
+        env
+    };
+
+    let manager_port = port(&topology.manager_url)?;
+
+    let mut specs = vec![
+        ContainerSpec {
+            name: "minio-server".to_owned(),
+            image: MINIO_IMAGE.to_owned(),
+            cmd: Some(
+                ["server", "--console-address", ":9001", "/data"]
+                    .map(str::to_owned)
+                    .to_vec(),
+            ),
+            entrypoint: None,
+            env: vec![
+                "MINIO_ROOT_USER=minioadmin".to_owned(),
+                "MINIO_ROOT_PASSWORD=minioadmin".to_owned(),
+            ],
+            ports: vec![9000, 9001],
+            volume: None,
+            restart_on_failure: false,
+            readiness: Readiness::Http("http://127.0.0.1:9000/minio/health/live".to_owned()),
+            build_context: None,
+        },
+        ContainerSpec {
+            name: "create-bucket".to_owned(),
+            image: MINIO_CLIENT_IMAGE.to_owned(),
+            cmd: None,
+            entrypoint: Some(vec![
+                "/bin/sh".to_owned(),
+                "-c".to_owned(),
+                "/usr/bin/mc alias set modelardb-minio http://minio-server:9000 minioadmin minioadmin; \
+                 /usr/bin/mc mb --ignore-existing modelardb-minio/modelardb; \
+                 /usr/bin/mc mb --ignore-existing modelardb-minio/comparison; \
+                 exit 0;"
+                    .to_owned(),
+            ]),
+            env: vec![],
+            ports: vec![],
+            volume: None,
+            restart_on_failure: false,
+            readiness: Readiness::Exited,
+            build_context: None,
+        },
+        ContainerSpec {
+            name: "modelardb-manager".to_owned(),
+            image: MODELARDB_IMAGE.to_owned(),
+            cmd: Some(
+                ["target/release/modelardbm", "s3://modelardb"]
+                    .map(str::to_owned)
+                    .to_vec(),
+            ),
+            entrypoint: None,
+            env: [aws_env.clone(), vec![format!("MODELARDBM_PORT={manager_port}")]].concat(),
+            ports: vec![manager_port],
+            volume: None,
+            restart_on_failure: false,
+            readiness: Readiness::Flight(topology.manager_url.clone()),
+            build_context: None,
+        },
+    ];
+
+    let manager_address = format!("grpc://modelardb-manager:{manager_port}");
+
+    for (index, (modelardb_node, _comparison_node)) in
+        topology.configured_edge_nodes.iter().enumerate()
+    {
+        let edge_port = port(modelardb_node.url())?;
+
+        specs.push(ContainerSpec {
+            name: format!("modelardb-edge-{}", index + 1),
+            image: MODELARDB_IMAGE.to_owned(),
+            cmd: Some(vec![
+                "target/release/modelardbd".to_owned(),
+                "edge".to_owned(),
+                "data/edge".to_owned(),
+                manager_address.clone(),
+            ]),
+            entrypoint: None,
+            env: modelardb_env(edge_port),
+            ports: vec![edge_port],
+            volume: Some((
+                format!("{PROJECT_NAME}_modelardb-{edge_port}"),
+                "/usr/src/app/data/edge".to_owned(),
+            )),
+            restart_on_failure: true,
+            readiness: Readiness::Flight(modelardb_node.url().to_owned()),
+            build_context: None,
+        });
+    }
+
+    let modelardb_cloud_urls = topology
+        .nodes
+        .iter()
+        .filter(|node| {
+            node.node_type == NodeType::Modelardb && node.server_mode == ServerMode::Cloud
+        })
+        .filter_map(|node| node.url.clone());
+
+    for (index, cloud_url) in modelardb_cloud_urls.enumerate() {
+        let cloud_port = port(&cloud_url)?;
+
+        let name = if index == 0 {
+            "modelardb-cloud".to_owned()
+        } else {
+            format!("modelardb-cloud-{}", index + 1)
+        };
+
+        specs.push(ContainerSpec {
+            name,
+            image: MODELARDB_IMAGE.to_owned(),
+            cmd: Some(vec![
+                "target/release/modelardbd".to_owned(),
+                "cloud".to_owned(),
+                "data/cloud".to_owned(),
+                manager_address.clone(),
+            ]),
+            entrypoint: None,
+            env: modelardb_env(cloud_port),
+            ports: vec![cloud_port],
+            volume: None,
+            restart_on_failure: true,
+            readiness: Readiness::Flight(cloud_url),
+            build_context: None,
+        });
+    }
+
+    for (index, (_modelardb_node, comparison_node)) in
+        topology.configured_edge_nodes.iter().enumerate()
+    {
+        let comparison_port = port(comparison_node.url())?;
+
+        specs.push(ContainerSpec {
+            name: format!("comparison-{}", index + 1),
+            image: COMPARISON_IMAGE.to_owned(),
+            cmd: None,
+            entrypoint: None,
+            env: vec![format!("FLIGHT_PORT={comparison_port}")],
+            ports: vec![comparison_port],
+            volume: Some((
+                format!("{PROJECT_NAME}_comparison-{comparison_port}"),
+                "/usr/src/app/data".to_owned(),
+            )),
+            restart_on_failure: false,
+            readiness: Readiness::Flight(comparison_node.url().to_owned()),
+            build_context: Some("comparison-system".to_owned()),
+        });
+    }
+
+    let comparison_cloud_urls = topology
+        .nodes
+        .iter()
+        .filter(|node| {
+            node.node_type == NodeType::Comparison && node.server_mode == ServerMode::Cloud
+        })
+        .filter_map(|node| node.url.clone());
+
+    for (index, cloud_url) in comparison_cloud_urls.enumerate() {
+        let cloud_port = port(&cloud_url)?;
+
+        let name = if index == 0 {
+            "comparison-cloud".to_owned()
+        } else {
+            format!("comparison-cloud-{}", index + 1)
+        };
+
+        specs.push(ContainerSpec {
+            name,
+            image: COMPARISON_IMAGE.to_owned(),
+            cmd: None,
+            entrypoint: None,
+            env: vec![format!("FLIGHT_PORT={cloud_port}")],
+            ports: vec![cloud_port],
+            volume: Some((
+                format!("{PROJECT_NAME}_comparison-{cloud_port}"),
+                "/usr/src/app/data".to_owned(),
+            )),
+            restart_on_failure: false,
+            readiness: Readiness::Flight(cloud_url),
+            build_context: Some("comparison-system".to_owned()),
+        });
+    }
+
+    Ok(specs)
+}
@@ -0,0 +1,996 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::array::{AsArray, RecordBatch};
+use arrow::compute;
+use arrow::csv::reader::Format;
+use arrow::csv::ReaderBuilder;
+use arrow::datatypes::{ArrowPrimitiveType, DataType, Field, Schema, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::ipc::reader::FileReader;
+use datafusion::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use datafusion::parquet::arrow::{ParquetRecordBatchStreamBuilder, ProjectionMask};
+use futures_util::stream::{self, BoxStream};
+use futures_util::{StreamExt, TryStreamExt};
+use modelardb_types::types::{ArrowTimestamp, ArrowValue, TimestampArray};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::error::DemoError;
+use crate::generator::{self, GeneratorConfig};
+
+/// Names of the ten fields in the bundled `wind_cleaned.parquet` data set in the order they are
+/// stored in the file.
+pub(super) const WIND_FIELD_NAMES: [&str; 10] = [
+    "wind_speed",
+    "pitch_angle",
+    "rotor_speed",
+    "active_power",
+    "cos_nacelle_dir",
+    "sin_nacelle_dir",
+    "cos_wind_dir",
+    "sin_wind_dir",
+    "cor_nacelle_direction",
+    "cor_wind_direction",
+];
+
+/// Names of the tags that are added to each row to identify the park and turbine it is from.
+const GENERATED_TAG_NAMES: [&str; 2] = ["park_id", "windmill_id"];
+
+/// The number of rows read to infer the schema of a CSV file.
+const CSV_SCHEMA_INFERENCE_ROWS: usize = 1000;
+
+/// The number of batches read ahead of the ingestion from CSV and Apache Arrow IPC files.
+const READ_AHEAD_BATCHES: usize = 2;
+
+type RecordBatchStream = BoxStream<'static, Result<RecordBatch, DemoError>>;
+
+/// The file formats data sets can be read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DatasetFormat {
+    Parquet,
+    Csv,
+    Ipc,
+}
+
+impl DatasetFormat {
+    fn try_from_path(path: &Path) -> Result<Self, DemoError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+
+        match extension.as_deref() {
+            Some("parquet") => Ok(Self::Parquet),
+            Some("csv") => Ok(Self::Csv),
+            Some("arrow" | "ipc" | "feather") => Ok(Self::Ipc),
+            _ => Err(DemoError::Configuration(format!(
+                "The format of {} is not supported, expected a .parquet, .csv, or .arrow file.",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// A column in the source file, referenced either by its position or by its name.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub(super) enum SourceColumn {
+    Index(usize),
+    Name(String),
+}
+
+/// A column in the source file and the name of the column it is ingested into.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(super) struct MappedColumn {
+    pub(super) source: SourceColumn,
+    /// The name of the column in the table. Defaults to the name of the source column.
+    #[serde(default)]
+    pub(super) name: Option<String>,
+}
+
+/// Which columns in a source file are the timestamp, tag, and field columns of the table. Tags are
+/// ingested as strings and fields as floats. The timestamps are generated during ingestion unless
+/// the data set is replayed, which requires a timestamp column.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(super) struct ColumnMapping {
+    #[serde(default)]
+    pub(super) timestamp: Option<MappedColumn>,
+    #[serde(default)]
+    pub(super) tags: Vec<MappedColumn>,
+    pub(super) fields: Vec<MappedColumn>,
+}
+
+impl ColumnMapping {
+    /// Propose a mapping for the file at `path` from its schema. The first timestamp or date column
+    /// is the timestamp column, string columns are tags, and numeric columns are fields. Columns
+    /// with other types or with the names of the generated tags are not included.
+    pub(super) fn try_propose(path: &Path) -> Result<Self, DemoError> {
+        let format = DatasetFormat::try_from_path(path)?;
+        let source_schema = read_schema(path, format)?;
+
+        let mut mapping = Self {
+            timestamp: None,
+            tags: vec![],
+            fields: vec![],
+        };
+
+        for field in source_schema.fields() {
+            if GENERATED_TAG_NAMES.contains(&field.name().as_str()) {
+                continue;
+            }
+
+            let column = MappedColumn {
+                source: SourceColumn::Name(field.name().clone()),
+                name: None,
+            };
+
+            match field.data_type() {
+                DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => {
+                    if mapping.timestamp.is_none() {
+                        mapping.timestamp = Some(column);
+                    }
+                }
+                data_type if is_string(data_type) => mapping.tags.push(column),
+                DataType::Dictionary(_, value_type) if is_string(value_type) => {
+                    mapping.tags.push(column)
+                }
+                data_type if data_type.is_numeric() => mapping.fields.push(column),
+                _ => (),
+            }
+        }
+
+        Ok(mapping)
+    }
+
+    /// Replace the parts of the mapping that are given in `overrides`.
+    pub(super) fn with_overrides(mut self, overrides: ColumnMappingOverrides) -> Self {
+        if let Some(timestamp) = overrides.timestamp {
+            self.timestamp = Some(timestamp);
+        }
+
+        if let Some(tags) = overrides.tags {
+            self.tags = tags;
+        }
+
+        if let Some(fields) = overrides.fields {
+            self.fields = fields;
+        }
+
+        self
+    }
+
+    /// The mapping of the bundled data set, whose first ten columns are the fields of the table.
+    pub(super) fn wind() -> Self {
+        let fields = WIND_FIELD_NAMES
+            .iter()
+            .enumerate()
+            .map(|(index, name)| MappedColumn {
+                source: SourceColumn::Index(index),
+                name: Some((*name).to_owned()),
+            })
+            .collect();
+
+        Self {
+            timestamp: None,
+            tags: vec![],
+            fields,
+        }
+    }
+}
+
+/// Parts of a proposed [`ColumnMapping`] that are replaced by the caller.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub(super) struct ColumnMappingOverrides {
+    #[serde(default)]
+    pub(super) timestamp: Option<MappedColumn>,
+    #[serde(default)]
+    pub(super) tags: Option<Vec<MappedColumn>>,
+    #[serde(default)]
+    pub(super) fields: Option<Vec<MappedColumn>>,
+}
+
+/// Where the rows that are ingested come from.
+#[derive(Clone)]
+pub(super) enum DataSource {
+    /// Rows read from a data set in a file.
+    File(Dataset),
+    /// Rows generated by synthetic wind turbines.
+    Synthetic(GeneratorConfig),
+}
+
+impl DataSource {
+    /// Schema of the table the rows are ingested into.
+    pub(super) fn table_schema(&self) -> SchemaRef {
+        match self {
+            Self::File(dataset) => dataset.table_schema(),
+            Self::Synthetic(_) => generator::table_schema(),
+        }
+    }
+
+    /// The variant of the schema of the table whose field columns are nullable, so sensor
+    /// dropouts can be ingested as null values.
+    pub(super) fn nullable_table_schema(&self) -> SchemaRef {
+        let table_schema = self.table_schema();
+
+        let fields: Vec<Field> = table_schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let nullable = *field.data_type() == ArrowValue::DATA_TYPE;
+                field.as_ref().clone().with_nullable(nullable)
+            })
+            .collect();
+
+        Arc::new(Schema::new(fields))
+    }
+
+    /// Names of the field columns in the table, i.e., the columns that are compressed within an
+    /// error bound.
+    pub(super) fn field_column_names(&self) -> Vec<String> {
+        self.table_schema()
+            .fields()
+            .iter()
+            .filter(|field| *field.data_type() == ArrowValue::DATA_TYPE)
+            .map(|field| field.name().clone())
+            .collect()
+    }
+
+    /// If the rows have timestamps that can be used when replaying them.
+    pub(super) fn has_timestamps(&self) -> bool {
+        match self {
+            Self::File(dataset) => dataset.has_timestamps(),
+            Self::Synthetic(_) => false,
+        }
+    }
+}
+
+/// A source file and the columns in it that are ingested into the table.
+#[derive(Clone)]
+pub(super) struct Dataset {
+    path: PathBuf,
+    format: DatasetFormat,
+    source_schema: SchemaRef,
+    /// Positions of the columns that are read from the source file in ascending order.
+    source_columns: Vec<usize>,
+    /// Positions of the mapped tag columns followed by the mapped field columns in the columns that
+    /// are read from the source file.
+    projection: Vec<usize>,
+    /// Schema of the table, i.e., the timestamp, the generated tags, and the mapped columns.
+    table_schema: SchemaRef,
+    /// Schema of the mapped columns after they have been read and cast.
+    data_schema: SchemaRef,
+    /// If the first of the mapped columns is the timestamps in the source file.
+    has_timestamps: bool,
+}
+
+impl Dataset {
+    /// Read the schema of the file at `path` and resolve `mapping` against it. Returns
+    /// [`DemoError::Configuration`] if a mapped column does not exist or the mapping would create a
+    /// table with duplicate column names.
+    pub(super) fn try_new(path: PathBuf, mapping: &ColumnMapping) -> Result<Self, DemoError> {
+        let format = DatasetFormat::try_from_path(&path)?;
+        let source_schema = read_schema(&path, format)?;
+
+        let resolve = |column: &MappedColumn| -> Result<(usize, String), DemoError> {
+            let index = match &column.source {
+                SourceColumn::Index(index) if *index < source_schema.fields().len() => *index,
+                SourceColumn::Index(index) => {
+                    return Err(DemoError::Configuration(format!(
+                        "{} has no column at position {index}.",
+                        path.display()
+                    )))
+                }
+                SourceColumn::Name(name) => source_schema.index_of(name).map_err(|_| {
+                    DemoError::Configuration(format!("{} has no column {name}.", path.display()))
+                })?,
+            };
+
+            let name = column
+                .name
+                .clone()
+                .unwrap_or_else(|| source_schema.field(index).name().clone());
+
+            Ok((index, name))
+        };
+
+        if mapping.fields.is_empty() {
+            return Err(DemoError::Configuration(
+                "The column mapping must contain at least one field column.".to_owned(),
+            ));
+        }
+
+        let timestamp_name = match &mapping.timestamp {
+            Some(column) => resolve(column)?.1,
+            None => "timestamp".to_owned(),
+        };
+
+        let mut table_fields = vec![Field::new(
+            &timestamp_name,
+            ArrowTimestamp::DATA_TYPE,
+            false,
+        )];
+
+        for name in GENERATED_TAG_NAMES {
+            table_fields.push(Field::new(name, DataType::Utf8, false));
+        }
+
+        let mut projection = vec![];
+        let mut data_fields = vec![];
+
+        // The timestamps in the source file are read first, so they can be used when replaying.
+        let has_timestamps = mapping.timestamp.is_some();
+        if let Some(column) = &mapping.timestamp {
+            projection.push(resolve(column)?.0);
+            data_fields.push(Field::new(
+                &timestamp_name,
+                ArrowTimestamp::DATA_TYPE,
+                false,
+            ));
+        }
+
+        for (columns, data_type) in [
+            (&mapping.tags, DataType::Utf8),
+            (&mapping.fields, ArrowValue::DATA_TYPE),
+        ] {
+            for column in columns {
+                let (index, name) = resolve(column)?;
+                projection.push(index);
+                data_fields.push(Field::new(name, data_type.clone(), false));
+            }
+        }
+
+        table_fields.extend(data_fields[usize::from(has_timestamps)..].iter().cloned());
+
+        let mut column_names = HashSet::new();
+        for field in &table_fields {
+            if !column_names.insert(field.name()) {
+                return Err(DemoError::Configuration(format!(
+                    "The column mapping contains the column {} more than once.",
+                    field.name()
+                )));
+            }
+        }
+
+        // The readers return the columns in the order they are stored in the file, so the
+        // projection is applied to the columns that are read instead of to the source schema.
+        let mut source_columns = projection.clone();
+        source_columns.sort_unstable();
+        source_columns.dedup();
+
+        let projection = projection
+            .iter()
+            .map(|index| source_columns.binary_search(index).unwrap())
+            .collect();
+
+        Ok(Self {
+            path,
+            format,
+            source_schema,
+            source_columns,
+            projection,
+            table_schema: Arc::new(Schema::new(table_fields)),
+            data_schema: Arc::new(Schema::new(data_fields)),
+            has_timestamps,
+        })
+    }
+
+    /// The bundled data set at `path` using [`ColumnMapping::wind`].
+    pub(super) fn try_new_wind(path: PathBuf) -> Result<Self, DemoError> {
+        Self::try_new(path, &ColumnMapping::wind())
+    }
+
+    /// Schema of the table the data set is ingested into.
+    pub(super) fn table_schema(&self) -> SchemaRef {
+        self.table_schema.clone()
+    }
+
+    /// If the data set has a timestamp column that can be used when replaying it.
+    pub(super) fn has_timestamps(&self) -> bool {
+        self.has_timestamps
+    }
+
+    /// Split `record_batch` read from the data set into the timestamps from the source file, if the
+    /// data set has a timestamp column, and the tag and field columns.
+    pub(super) fn split_timestamps(
+        &self,
+        record_batch: &RecordBatch,
+    ) -> Result<(Option<TimestampArray>, RecordBatch), DemoError> {
+        if !self.has_timestamps {
+            return Ok((None, record_batch.clone()));
+        }
+
+        let timestamps = record_batch
+            .column(0)
+            .as_primitive::<ArrowTimestamp>()
+            .clone();
+
+        let columns: Vec<usize> = (1..record_batch.num_columns()).collect();
+        Ok((Some(timestamps), record_batch.project(&columns)?))
+    }
+
+    /// Count the rows in the data set. The count is read from the metadata of Parquet files, while
+    /// the other formats are read once without keeping more than a batch in memory.
+    pub(super) async fn try_num_rows(&self) -> Result<usize, DemoError> {
+        match self.format {
+            DatasetFormat::Parquet => {
+                let file = tokio::fs::File::open(&self.path)
+                    .await
+                    .map_err(|error| self.read_error(error))?;
+
+                let builder = ParquetRecordBatchStreamBuilder::new(file).await?;
+                Ok(builder.metadata().file_metadata().num_rows() as usize)
+            }
+            DatasetFormat::Csv | DatasetFormat::Ipc => {
+                let dataset = self.clone();
+                tokio::task::spawn_blocking(move || {
+                    dataset
+                        .record_batch_reader()?
+                        .try_fold(0, |num_rows, record_batch| {
+                            Ok(num_rows + record_batch?.num_rows())
+                        })
+                })
+                .await
+                .map_err(|error| DemoError::Schema(error.to_string()))?
+            }
+        }
+    }
+
+    /// Stream the mapped columns of the rows from `start` to `end` in the data set, with the tag
+    /// columns followed by the field columns, cast to the types used in the table. Parquet files
+    /// are read one row group at a time and only the row groups that contain the rows are read,
+    /// while the other formats are read at most [`READ_AHEAD_BATCHES`] batches ahead.
+    async fn try_stream(&self, start: usize, end: usize) -> Result<RecordBatchStream, DemoError> {
+        let dataset = self.clone();
+
+        match self.format {
+            DatasetFormat::Parquet => {
+                let file = tokio::fs::File::open(&self.path)
+                    .await
+                    .map_err(|error| self.read_error(error))?;
+
+                let builder = ParquetRecordBatchStreamBuilder::new(file).await?;
+
+                let mut row_groups = vec![];
+                let mut first_row = start;
+                let mut row_group_start = 0;
+                for (index, row_group) in builder.metadata().row_groups().iter().enumerate() {
+                    let row_group_end = row_group_start + row_group.num_rows() as usize;
+
+                    if row_group_end > start && row_group_start < end {
+                        if row_groups.is_empty() {
+                            first_row = row_group_start;
+                        }
+                        row_groups.push(index);
+                    }
+
+                    row_group_start = row_group_end;
+                }
+
+                let projection_mask = ProjectionMask::roots(
+                    builder.parquet_schema(),
+                    self.source_columns.iter().copied(),
+                );
+
+                let stream = builder
+                    .with_row_groups(row_groups)
+                    .with_projection(projection_mask)
+                    .with_offset(start - first_row)
+                    .with_limit(end - start)
+                    .build()?;
+
+                Ok(stream
+                    .map(move |record_batch| dataset.project(&record_batch?))
+                    .boxed())
+            }
+            DatasetFormat::Csv | DatasetFormat::Ipc => {
+                let (sender, receiver) = mpsc::channel(READ_AHEAD_BATCHES);
+
+                tokio::task::spawn_blocking(move || {
+                    if let Err(error) = dataset.read_blocking(start, end, &sender) {
+                        // The stream may have been dropped, in which case the error is not needed.
+                        let _ = sender.blocking_send(Err(error));
+                    }
+                });
+
+                Ok(stream::unfold(receiver, |mut receiver| async move {
+                    receiver
+                        .recv()
+                        .await
+                        .map(|record_batch| (record_batch, receiver))
+                })
+                .boxed())
+            }
+        }
+    }
+
+    /// Send the rows from `start` to `end` in a CSV or Apache Arrow IPC file to `sender` until all
+    /// of them have been sent or the receiver is dropped.
+    fn read_blocking(
+        &self,
+        start: usize,
+        end: usize,
+        sender: &mpsc::Sender<Result<RecordBatch, DemoError>>,
+    ) -> Result<(), DemoError> {
+        let mut row = 0;
+
+        for record_batch in self.record_batch_reader()? {
+            let record_batch = record_batch?;
+            let batch_start = row;
+            row += record_batch.num_rows();
+
+            if row <= start {
+                continue;
+            }
+
+            let offset = start.saturating_sub(batch_start);
+            let length = row.min(end) - batch_start - offset;
+            let record_batch = self.project(&record_batch.slice(offset, length))?;
+
+            if sender.blocking_send(Ok(record_batch)).is_err() || row >= end {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create a reader for the columns that are read from a CSV or Apache Arrow IPC file.
+    fn record_batch_reader(
+        &self,
+    ) -> Result<Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>> + Send>, DemoError> {
+        let file = File::open(&self.path).map_err(|error| self.read_error(error))?;
+
+        let reader: Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>> + Send> =
+            match self.format {
+                DatasetFormat::Csv => Box::new(
+                    ReaderBuilder::new(self.source_schema.clone())
+                        .with_header(true)
+                        .with_projection(self.source_columns.clone())
+                        .build(file)?,
+                ),
+                DatasetFormat::Ipc => Box::new(FileReader::try_new(
+                    file,
+                    Some(self.source_columns.clone()),
+                )?),
+                DatasetFormat::Parquet => unreachable!("Parquet files are read asynchronously."),
+            };
+
+        Ok(reader)
+    }
+
+    /// Select the mapped columns in `record_batch` and cast them to the types used in the table.
+    /// Returns [`DemoError::Configuration`] if one of the columns contains null values, as the
+    /// columns of the table are not nullable.
+    fn project(&self, record_batch: &RecordBatch) -> Result<RecordBatch, DemoError> {
+        let columns = self
+            .projection
+            .iter()
+            .zip(self.data_schema.fields())
+            .map(|(index, field)| {
+                let column = compute::cast(record_batch.column(*index), field.data_type())?;
+
+                if column.null_count() > 0 {
+                    return Err(DemoError::Configuration(format!(
+                        "The {} column in {} contains null values.",
+                        field.name(),
+                        self.path.display()
+                    )));
+                }
+
+                Ok(column)
+            })
+            .collect::<Result<Vec<_>, DemoError>>()?;
+
+        Ok(RecordBatch::try_new(self.data_schema.clone(), columns)?)
+    }
+
+    fn read_error(&self, error: std::io::Error) -> DemoError {
+        DemoError::Configuration(format!("Could not read {}: {error}", self.path.display()))
+    }
+}
+
+fn is_string(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+    )
+}
+
+/// Reads the rows in a partition of a [`Dataset`] in order without keeping the whole partition in
+/// memory, and starts over from the beginning of the partition when it reaches the end.
+pub(super) struct PartitionReader {
+    dataset: Dataset,
+    start: usize,
+    end: usize,
+    /// The row in the data set that is returned next.
+    position: usize,
+    /// The row in the data set that `stream` was opened at.
+    stream_start: usize,
+    stream: Option<RecordBatchStream>,
+    /// The rows of the last batch read from `stream` that have not been returned yet.
+    remaining: Option<RecordBatch>,
+    /// The number of times the reader has started over from the beginning of the partition.
+    rewinds: usize,
+    /// The number of times the partition is read before the reader stops at its end, or [`None`]
+    /// if the reader starts over each time.
+    max_passes: Option<usize>,
+}
+
+impl PartitionReader {
+    /// Create a reader for the rows from `start` to `end` in `dataset` that continues from `offset`
+    /// rows into the partition, counting the rows read in earlier passes, and stops after
+    /// `max_passes` passes if given.
+    pub(super) fn new(
+        dataset: Dataset,
+        start: usize,
+        end: usize,
+        offset: usize,
+        max_passes: Option<usize>,
+    ) -> Self {
+        let mut partition_reader = Self {
+            dataset,
+            start,
+            end,
+            position: start,
+            stream_start: start,
+            stream: None,
+            remaining: None,
+            rewinds: 0,
+            max_passes: max_passes.map(|max_passes| max_passes.max(1)),
+        };
+
+        partition_reader.seek_offset(offset);
+        partition_reader
+    }
+
+    /// Read the next `count` rows in the partition. Fewer rows are returned if the partition
+    /// contains fewer than `count` rows.
+    pub(super) async fn try_next(&mut self, count: usize) -> Result<RecordBatch, DemoError> {
+        let count = count.min(self.end - self.start);
+        let mut record_batches = vec![];
+        let mut num_rows = 0;
+
+        while num_rows < count {
+            if self.is_at_end() {
+                if self.is_finished() {
+                    break;
+                }
+
+                self.rewind();
+            }
+
+            let Some(record_batch) = self.next_batch().await? else {
+                // Stop if the file contains fewer rows than when it was partitioned.
+                if self.position == self.stream_start {
+                    break;
+                }
+
+                // Otherwise, the partition ends where the file does.
+                self.position = self.end;
+                continue;
+            };
+
+            num_rows += self.take(record_batch, count - num_rows, &mut record_batches);
+        }
+
+        Ok(compute::concat_batches(
+            &self.dataset.data_schema,
+            &record_batches,
+        )?)
+    }
+
+    /// Read at most `max_rows` of the next rows in the partition that have a timestamp before
+    /// `end_timestamp`. The rows in the partition must be sorted by their timestamps in the source
+    /// file. Unlike [`Self::try_next`], the reader does not start over when it reaches the end of
+    /// the partition, so the caller can detect that the timestamps start over.
+    pub(super) async fn try_next_before(
+        &mut self,
+        max_rows: usize,
+        end_timestamp: i64,
+    ) -> Result<RecordBatch, DemoError> {
+        let mut record_batches = vec![];
+        let mut num_rows = 0;
+
+        while num_rows < max_rows && !self.is_at_end() {
+            let Some(record_batch) = self.next_batch().await? else {
+                // The file contains fewer rows than when it was partitioned.
+                self.position = self.end;
+                break;
+            };
+
+            let (timestamps, _data_points) = self.dataset.split_timestamps(&record_batch)?;
+            let timestamps = timestamps.ok_or_else(|| {
+                DemoError::Configuration("The data set has no timestamp column.".to_owned())
+            })?;
+
+            let before = timestamps
+                .values()
+                .partition_point(|timestamp| *timestamp < end_timestamp);
+
+            num_rows += self.take(
+                record_batch,
+                before.min(max_rows - num_rows),
+                &mut record_batches,
+            );
+
+            // The rest of the batch is after `end_timestamp` or more than `max_rows`.
+            if self.remaining.is_some() {
+                break;
+            }
+        }
+
+        Ok(compute::concat_batches(
+            &self.dataset.data_schema,
+            &record_batches,
+        )?)
+    }
+
+    /// Return the timestamp in the source file of the next row in the partition without reading
+    /// past it, or [`None`] if the reader is at the end of the partition.
+    pub(super) async fn try_peek_timestamp(&mut self) -> Result<Option<i64>, DemoError> {
+        while !self.is_at_end() {
+            let Some(record_batch) = self.next_batch().await? else {
+                return Ok(None);
+            };
+
+            if record_batch.num_rows() > 0 {
+                let (timestamps, _data_points) = self.dataset.split_timestamps(&record_batch)?;
+                let timestamp = timestamps.map(|timestamps| timestamps.value(0));
+                self.remaining = Some(record_batch);
+                return Ok(timestamp);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// If all the rows in the partition have been read since the reader last started over.
+    pub(super) fn is_at_end(&self) -> bool {
+        self.position >= self.end
+    }
+
+    /// If the reader is at the end of its last pass, so it does not start over.
+    pub(super) fn is_finished(&self) -> bool {
+        self.is_at_end()
+            && self
+                .max_passes
+                .is_some_and(|max_passes| self.rewinds + 1 >= max_passes)
+    }
+
+    /// The number of times all of the rows in the partition have been read.
+    pub(super) fn passes(&self) -> usize {
+        self.rewinds + usize::from(self.is_at_end())
+    }
+
+    /// Start over from the beginning of the partition.
+    pub(super) fn rewind(&mut self) {
+        self.rewinds += 1;
+        self.seek(self.start);
+    }
+
+    /// The data set the reader reads from.
+    pub(super) fn dataset(&self) -> &Dataset {
+        &self.dataset
+    }
+
+    /// Schema of the batches returned by the reader.
+    pub(super) fn schema(&self) -> SchemaRef {
+        self.dataset.data_schema.clone()
+    }
+
+    /// Skip the next `count` rows in the partition without reading them, as if they were read with
+    /// [`Self::try_next`].
+    pub(super) fn skip(&mut self, count: usize) {
+        let length = self.end - self.start;
+        self.seek_offset(self.offset() + count.min(length));
+    }
+
+    /// The number of rows read from the partition, including the rows read in earlier passes.
+    pub(super) fn offset(&self) -> usize {
+        self.rewinds * (self.end - self.start) + self.position - self.start
+    }
+
+    /// Seek to `offset` rows into the partition, counting the rows read in earlier passes. The
+    /// reader stays at the end of the partition if `offset` is past its last pass.
+    fn seek_offset(&mut self, offset: usize) {
+        let length = self.end - self.start;
+        if length == 0 {
+            return;
+        }
+
+        let passes = offset / length;
+        let (rewinds, position) = match self.max_passes {
+            Some(max_passes) if passes >= max_passes => (max_passes - 1, self.end),
+            _ => (passes, self.start + offset % length),
+        };
+
+        self.rewinds = rewinds;
+        self.seek(position);
+    }
+
+    fn seek(&mut self, position: usize) {
+        self.position = position;
+        self.stream = None;
+        self.remaining = None;
+    }
+
+    /// Return the rows that have not been returned yet from the last batch, or the next batch from
+    /// the stream which is opened at the current position if it is not open.
+    async fn next_batch(&mut self) -> Result<Option<RecordBatch>, DemoError> {
+        if let Some(record_batch) = self.remaining.take() {
+            return Ok(Some(record_batch));
+        }
+
+        if self.stream.is_none() {
+            let stream = self.dataset.try_stream(self.position, self.end).await?;
+            self.stream = Some(stream);
+            self.stream_start = self.position;
+        }
+
+        self.stream.as_mut().unwrap().try_next().await
+    }
+
+    /// Add the first `length` rows of `record_batch` to `record_batches`, keep the other rows for
+    /// the next read, and return the number of rows added.
+    fn take(
+        &mut self,
+        record_batch: RecordBatch,
+        length: usize,
+        record_batches: &mut Vec<RecordBatch>,
+    ) -> usize {
+        let length = length.min(record_batch.num_rows());
+        if length < record_batch.num_rows() {
+            self.remaining = Some(record_batch.slice(length, record_batch.num_rows() - length));
+        }
+
+        record_batches.push(record_batch.slice(0, length));
+        self.position += length;
+
+        length
+    }
+}
+
+/// Read the schema of the file at `path` without reading its data, except for CSV files where the
+/// schema is inferred from the first rows.
+fn read_schema(path: &Path, format: DatasetFormat) -> Result<SchemaRef, DemoError> {
+    let file = File::open(path).map_err(|error| {
+        DemoError::Configuration(format!("Could not read {}: {error}", path.display()))
+    })?;
+
+    let schema = match format {
+        DatasetFormat::Parquet => ParquetRecordBatchReaderBuilder::try_new(file)?
+            .schema()
+            .clone(),
+        DatasetFormat::Csv => {
+            let (schema, _rows) = Format::default()
+                .with_header(true)
+                .infer_schema(file, Some(CSV_SCHEMA_INFERENCE_ROWS))?;
+            Arc::new(schema)
+        }
+        DatasetFormat::Ipc => FileReader::try_new(file, None)?.schema(),
+    };
+
+    Ok(schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fmt::Write;
+
+    use tauri::async_runtime;
+
+    /// Write a CSV file named `name` with a `value` column containing 0 to `num_rows` - 1 and
+    /// return it as a data set.
+    fn create_dataset(name: &str, num_rows: usize) -> Dataset {
+        let path = std::env::temp_dir().join(format!("modelardb-demo-{name}.csv"));
+
+        let mut csv = "value\n".to_owned();
+        for row in 0..num_rows {
+            writeln!(csv, "{row}.0").unwrap();
+        }
+        std::fs::write(&path, csv).unwrap();
+
+        let mapping = ColumnMapping::try_propose(&path).unwrap();
+        Dataset::try_new(path, &mapping).unwrap()
+    }
+
+    fn try_next_values(partition_reader: &mut PartitionReader, count: usize) -> Vec<f32> {
+        let record_batch = async_runtime::block_on(partition_reader.try_next(count)).unwrap();
+        record_batch
+            .column(0)
+            .as_primitive::<ArrowValue>()
+            .values()
+            .to_vec()
+    }
+
+    #[test]
+    fn test_try_next_starts_over_at_end_of_partition() {
+        let dataset = create_dataset("starts-over", 10);
+        let mut partition_reader = PartitionReader::new(dataset, 2, 6, 0, None);
+
+        assert_eq!(try_next_values(&mut partition_reader, 3), [2.0, 3.0, 4.0]);
+        assert_eq!(try_next_values(&mut partition_reader, 3), [5.0, 2.0, 3.0]);
+        assert_eq!(partition_reader.passes(), 1);
+        assert_eq!(partition_reader.offset(), 6);
+        assert!(!partition_reader.is_finished());
+    }
+
+    #[test]
+    fn test_new_seeks_to_offset_in_later_pass() {
+        let dataset = create_dataset("seeks-offset", 10);
+        let mut partition_reader = PartitionReader::new(dataset, 2, 6, 6, None);
+
+        assert_eq!(partition_reader.passes(), 1);
+        assert_eq!(try_next_values(&mut partition_reader, 2), [4.0, 5.0]);
+        assert_eq!(partition_reader.passes(), 2);
+        assert_eq!(partition_reader.offset(), 8);
+    }
+
+    #[test]
+    fn test_skip_continues_in_next_pass() {
+        let dataset = create_dataset("skip", 10);
+        let mut partition_reader = PartitionReader::new(dataset, 0, 4, 0, None);
+
+        partition_reader.skip(3);
+        assert_eq!(try_next_values(&mut partition_reader, 2), [3.0, 0.0]);
+        assert_eq!(partition_reader.offset(), 5);
+    }
+
+    #[test]
+    fn test_is_finished_after_max_passes() {
+        let dataset = create_dataset("max-passes", 10);
+        let mut partition_reader = PartitionReader::new(dataset, 0, 4, 0, Some(2));
+
+        assert_eq!(try_next_values(&mut partition_reader, 3), [0.0, 1.0, 2.0]);
+        assert_eq!(try_next_values(&mut partition_reader, 3), [3.0, 0.0, 1.0]);
+        assert!(!partition_reader.is_finished());
+
+        assert_eq!(try_next_values(&mut partition_reader, 3), [2.0, 3.0]);
+        assert!(partition_reader.is_finished());
+        assert_eq!(partition_reader.passes(), 2);
+
+        assert!(try_next_values(&mut partition_reader, 3).is_empty());
+    }
+
+    #[test]
+    fn test_new_stays_at_end_if_offset_is_past_last_pass() {
+        let dataset = create_dataset("past-last-pass", 10);
+        let partition_reader = PartitionReader::new(dataset, 0, 4, 100, Some(2));
+
+        assert!(partition_reader.is_finished());
+        assert_eq!(partition_reader.passes(), 2);
+        assert_eq!(partition_reader.offset(), 8);
+    }
+
+    #[test]
+    fn test_table_schema_is_not_nullable() {
+        // The schema inferred for a CSV file is always nullable.
+        let dataset = create_dataset("not-nullable", 2);
+
+        let table_schema = dataset.table_schema();
+        assert!(table_schema
+            .fields()
+            .iter()
+            .all(|field| !field.is_nullable()));
+    }
+
+    #[test]
+    fn test_try_next_fails_for_null_values() {
+        let path = std::env::temp_dir().join("modelardb-demo-null-values.csv");
+        std::fs::write(&path, "value,other\n1.0,2.0\n,3.0\n").unwrap();
+
+        let mapping = ColumnMapping::try_propose(&path).unwrap();
+        let dataset = Dataset::try_new(path, &mapping).unwrap();
+        let mut partition_reader = PartitionReader::new(dataset, 0, 2, 0, None);
+
+        assert!(matches!(
+            async_runtime::block_on(partition_reader.try_next(2)),
+            Err(DemoError::Configuration(_))
+        ));
+    }
+}
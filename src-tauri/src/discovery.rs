@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use arrow::array::{Array, StringArray};
+use arrow_flight::flight_service_client::FlightServiceClient;
+use arrow_flight::Action;
+use futures_util::TryStreamExt;
+use tauri::AppHandle;
+use tokio::sync::watch;
+use tokio::time;
+
+use crate::error::DemoError;
+use crate::events::{self, EventEmitter};
+use crate::topology::{RegisteredNode, ServerMode, Topology};
+use crate::util;
+
+/// How often the ModelarDB manager is asked for the nodes that are registered.
+const DISCOVERY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The action the ModelarDB manager is asked for the registered nodes with. It must be checked
+/// against the `do_action` implementation of the manager in the ModelarDB-RS revision pinned in
+/// `Cargo.toml` whenever that revision changes. If the manager does not support it, each attempt
+/// fails and the nodes in the nodes file are kept.
+const LIST_NODES_ACTION: &str = "ListNodes";
+
+/// Ask the ModelarDB manager at `manager_url` for the nodes that are registered. The manager
+/// responds to [`LIST_NODES_ACTION`] with Arrow IPC streams that have a `url` and a `mode` column.
+async fn registered_nodes(manager_url: &str) -> Result<Vec<RegisteredNode>, DemoError> {
+    let mut flight_client = FlightServiceClient::connect(manager_url.to_owned()).await?;
+
+    let action = Action {
+        r#type: LIST_NODES_ACTION.to_owned(),
+        body: vec![].into(),
+    };
+
+    let results = flight_client
+        .do_action(action)
+        .await?
+        .into_inner()
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let mut nodes = vec![];
+    for result in results {
+        for record_batch in util::try_convert_bytes_to_record_batches(&result.body)? {
+            let string_column = |name: &str| {
+                record_batch
+                    .column_by_name(name)
+                    .and_then(|column| column.as_any().downcast_ref::<StringArray>())
+                    .ok_or_else(|| {
+                        DemoError::Schema(format!(
+                            "The registered nodes are missing the {name} column."
+                        ))
+                    })
+            };
+
+            let urls = string_column("url")?;
+            let modes = string_column("mode")?;
+
+            for row_index in 0..record_batch.num_rows() {
+                let server_mode = match modes.value(row_index) {
+                    "edge" => ServerMode::Edge,
+                    "cloud" => ServerMode::Cloud,
+                    _ => continue,
+                };
+
+                nodes.push(RegisteredNode {
+                    url: urls.value(row_index).to_owned(),
+                    server_mode,
+                });
+            }
+        }
+    }
+
+    Ok(nodes)
+}
+
+/// Periodically ask the ModelarDB manager for the registered nodes and update the topology if
+/// they have changed. If the manager cannot be reached, the current topology is kept and a
+/// `task-error` event is emitted, but only for the first failure until discovery succeeds again so
+/// a manager that is down does not emit an event every [`DISCOVERY_INTERVAL`].
+pub(super) async fn discover_nodes_task<E: EventEmitter>(
+    emitter: E,
+    topology_sender: watch::Sender<Topology>,
+) {
+    let manager_url = topology_sender.borrow().manager_url.clone();
+    let mut failed = false;
+
+    loop {
+        match registered_nodes(&manager_url).await {
+            Ok(nodes) => {
+                failed = false;
+                topology_sender
+                    .send_if_modified(|topology| topology.apply_registered_nodes(&nodes));
+            }
+            Err(error) if !failed => {
+                failed = true;
+                events::emit_task_error(&emitter, crate::DISCOVERY_TASK, error);
+            }
+            Err(_error) => (),
+        }
+
+        time::sleep(DISCOVERY_INTERVAL).await;
+    }
+}
+
+/// Emit a `cluster-topology` event with the current topology and then each time it changes.
+pub(super) async fn emit_cluster_topology_task(
+    app: AppHandle,
+    mut topology_receiver: watch::Receiver<Topology>,
+) {
+    loop {
+        let cluster_topology = topology_receiver.borrow_and_update().cluster_topology();
+        app.emit_event("cluster-topology", cluster_topology);
+
+        if topology_receiver.changed().await.is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use arrow::array::{RecordBatch, UInt32Array};
+use arrow::compute;
+use serde::{Deserialize, Serialize};
+
+use crate::error::DemoError;
+use crate::util::Random;
+
+/// The number of recently written batches per edge node that duplicates are chosen from.
+const RECENT_BATCHES: usize = 10;
+
+/// The stream of random numbers the disorder of each edge node is drawn from.
+const DISORDER_STREAM: u64 = 0x9e37_79b9;
+
+/// How the batches ingested into each edge node deviate from strictly increasing timestamps, like
+/// the traffic from field gateways that buffer and retry.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub(super) struct Disorder {
+    /// Shuffle the rows of each batch within windows of this many consecutive rows. Rows are not
+    /// shuffled if it is 0 or 1.
+    pub(super) shuffle_window_rows: usize,
+    /// The probability that one of the recently written batches is sent again after a batch.
+    pub(super) duplicate_probability: f64,
+    /// The probability that a batch is held back and sent `late_seconds` later.
+    pub(super) late_probability: f64,
+    pub(super) late_seconds: u64,
+    /// Seed for the disorder so a run can be repeated. Defaults to the current time.
+    pub(super) seed: Option<u64>,
+}
+
+impl Default for Disorder {
+    fn default() -> Self {
+        Self {
+            shuffle_window_rows: 0,
+            duplicate_probability: 0.0,
+            late_probability: 0.0,
+            late_seconds: 120,
+            seed: None,
+        }
+    }
+}
+
+/// Why a batch is written to an edge node.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum BatchKind {
+    /// The batch is written when its rows are ingested.
+    Regular,
+    /// The batch has already been written before.
+    Duplicate,
+    /// The batch was held back and is written after later batches.
+    Late,
+}
+
+/// Applies the disorder to the batches ingested into an edge node.
+pub(super) struct DisorderGenerator {
+    disorder: Disorder,
+    random: Random,
+    /// The most recently written batches, which duplicates are chosen from.
+    recent_batches: VecDeque<RecordBatch>,
+    /// Batches that are held back and when they should be written.
+    late_batches: VecDeque<(Instant, RecordBatch)>,
+}
+
+impl DisorderGenerator {
+    /// Create a generator for the edge node at `node_index`.
+    pub(super) fn new(disorder: &Disorder, node_index: usize) -> Self {
+        let random = Random::for_node(disorder.seed, node_index, DISORDER_STREAM);
+
+        Self {
+            disorder: disorder.clone(),
+            random,
+            recent_batches: VecDeque::new(),
+            late_batches: VecDeque::new(),
+        }
+    }
+
+    /// Return the batches to write to the edge node after `record_batch` has been ingested into
+    /// it, i.e., `record_batch` with its rows shuffled unless it is held back, a duplicate of a
+    /// recently written batch, and the held back batches that are due.
+    pub(super) fn next_batches(
+        &mut self,
+        record_batch: RecordBatch,
+    ) -> Result<Vec<(RecordBatch, BatchKind)>, DemoError> {
+        let now = Instant::now();
+        let mut batches = vec![];
+
+        // The batches are held back for the same time, so they are due in the order they were
+        // added.
+        while self
+            .late_batches
+            .front()
+            .is_some_and(|(due, _late_batch)| *due <= now)
+        {
+            let (_due, late_batch) = self.late_batches.pop_front().unwrap();
+            batches.push((late_batch, BatchKind::Late));
+        }
+
+        let record_batch = self.shuffle(record_batch)?;
+
+        if self.random.next_f64() < self.disorder.late_probability {
+            let due = now + Duration::from_secs(self.disorder.late_seconds);
+            self.late_batches.push_back((due, record_batch));
+            return Ok(batches);
+        }
+
+        let duplicate = if !self.recent_batches.is_empty()
+            && self.random.next_f64() < self.disorder.duplicate_probability
+        {
+            let index = (self.random.next_f64() * self.recent_batches.len() as f64) as usize;
+            self.recent_batches
+                .get(index.min(self.recent_batches.len() - 1))
+                .cloned()
+        } else {
+            None
+        };
+
+        self.recent_batches.push_back(record_batch.clone());
+        if self.recent_batches.len() > RECENT_BATCHES {
+            self.recent_batches.pop_front();
+        }
+
+        batches.push((record_batch, BatchKind::Regular));
+        batches.extend(duplicate.map(|duplicate| (duplicate, BatchKind::Duplicate)));
+
+        Ok(batches)
+    }
+
+    /// Return the batches that are held back, which are written when the ingestion completes even
+    /// though they are not due yet.
+    pub(super) fn held_back_batches(&mut self) -> Vec<(RecordBatch, BatchKind)> {
+        self.late_batches
+            .drain(..)
+            .map(|(_due, late_batch)| (late_batch, BatchKind::Late))
+            .collect()
+    }
+
+    /// Take the batches that are held back and when they are due, so they can be written to another
+    /// edge node when the edge nodes change.
+    pub(super) fn take_late_batches(&mut self) -> VecDeque<(Instant, RecordBatch)> {
+        std::mem::take(&mut self.late_batches)
+    }
+
+    /// Hold back `late_batches` taken from another generator until they are due.
+    pub(super) fn add_late_batches(&mut self, late_batches: VecDeque<(Instant, RecordBatch)>) {
+        self.late_batches.extend(late_batches);
+
+        // The batches are released from the front, so they must stay ordered by when they are due.
+        self.late_batches
+            .make_contiguous()
+            .sort_by_key(|(due, _late_batch)| *due);
+    }
+
+    /// Shuffle the rows of `record_batch` within windows of consecutive rows.
+    fn shuffle(&mut self, record_batch: RecordBatch) -> Result<RecordBatch, DemoError> {
+        let window_rows = self.disorder.shuffle_window_rows;
+        if window_rows < 2 {
+            return Ok(record_batch);
+        }
+
+        let mut indices: Vec<u32> = (0..record_batch.num_rows() as u32).collect();
+
+        // Shuffle each window with the Fisher-Yates algorithm.
+        for window in indices.chunks_mut(window_rows) {
+            for index in (1..window.len()).rev() {
+                let other_index = (self.random.next_f64() * (index + 1) as f64) as usize;
+                window.swap(index, other_index.min(index));
+            }
+        }
+
+        Ok(compute::take_record_batch(
+            &record_batch,
+            &UInt32Array::from(indices),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use arrow::array::{AsArray, Int32Array};
+    use arrow::datatypes::{DataType, Field, Int32Type, Schema};
+
+    fn record_batch(values: impl IntoIterator<Item = i32>) -> RecordBatch {
+        let schema = Schema::new(vec![Field::new("value", DataType::Int32, false)]);
+        let values = Int32Array::from_iter_values(values);
+
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap()
+    }
+
+    fn values(record_batch: &RecordBatch) -> Vec<i32> {
+        record_batch
+            .column(0)
+            .as_primitive::<Int32Type>()
+            .values()
+            .to_vec()
+    }
+
+    fn disorder() -> Disorder {
+        Disorder {
+            seed: Some(1),
+            ..Disorder::default()
+        }
+    }
+
+    #[test]
+    fn test_next_batches_keeps_order_without_disorder() {
+        let mut disorder_generator = DisorderGenerator::new(&disorder(), 0);
+
+        let batches = disorder_generator.next_batches(record_batch(0..8)).unwrap();
+
+        assert_eq!(batches.len(), 1);
+        assert!(matches!(batches[0].1, BatchKind::Regular));
+        assert_eq!(values(&batches[0].0), (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_next_batches_shuffles_within_windows() {
+        let disorder = Disorder {
+            shuffle_window_rows: 4,
+            ..disorder()
+        };
+        let mut disorder_generator = DisorderGenerator::new(&disorder, 0);
+
+        let batches = disorder_generator
+            .next_batches(record_batch(0..10))
+            .unwrap();
+        let shuffled_values = values(&batches[0].0);
+
+        for (window_index, window) in shuffled_values.chunks(4).enumerate() {
+            let mut window = window.to_vec();
+            window.sort_unstable();
+
+            let start = window_index as i32 * 4;
+            let expected: Vec<i32> = (start..(start + 4).min(10)).collect();
+            assert_eq!(window, expected);
+        }
+    }
+
+    #[test]
+    fn test_next_batches_duplicates_recent_batch() {
+        let disorder = Disorder {
+            duplicate_probability: 1.0,
+            ..disorder()
+        };
+        let mut disorder_generator = DisorderGenerator::new(&disorder, 0);
+
+        // There are no recently written batches to duplicate yet.
+        let batches = disorder_generator.next_batches(record_batch(0..2)).unwrap();
+        assert_eq!(batches.len(), 1);
+
+        let batches = disorder_generator.next_batches(record_batch(2..4)).unwrap();
+        assert_eq!(batches.len(), 2);
+        assert!(matches!(batches[0].1, BatchKind::Regular));
+        assert_eq!(values(&batches[0].0), [2, 3]);
+        assert!(matches!(batches[1].1, BatchKind::Duplicate));
+        assert_eq!(values(&batches[1].0), [0, 1]);
+    }
+
+    #[test]
+    fn test_next_batches_releases_late_batches_when_due() {
+        let disorder = Disorder {
+            late_probability: 1.0,
+            late_seconds: 0,
+            ..disorder()
+        };
+        let mut disorder_generator = DisorderGenerator::new(&disorder, 0);
+
+        assert!(disorder_generator
+            .next_batches(record_batch(0..2))
+            .unwrap()
+            .is_empty());
+
+        let batches = disorder_generator.next_batches(record_batch(2..4)).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert!(matches!(batches[0].1, BatchKind::Late));
+        assert_eq!(values(&batches[0].0), [0, 1]);
+
+        let held_back_batches = disorder_generator.held_back_batches();
+        assert_eq!(held_back_batches.len(), 1);
+        assert_eq!(values(&held_back_batches[0].0), [2, 3]);
+        assert!(disorder_generator.held_back_batches().is_empty());
+    }
+
+    #[test]
+    fn test_held_back_batches_before_they_are_due() {
+        let disorder = Disorder {
+            late_probability: 1.0,
+            ..disorder()
+        };
+        let mut disorder_generator = DisorderGenerator::new(&disorder, 0);
+
+        for start in [0, 2, 4] {
+            let batches = disorder_generator
+                .next_batches(record_batch(start..start + 2))
+                .unwrap();
+            assert!(batches.is_empty());
+        }
+
+        let held_back_values: Vec<Vec<i32>> = disorder_generator
+            .held_back_batches()
+            .iter()
+            .map(|(record_batch, _kind)| values(record_batch))
+            .collect();
+        assert_eq!(held_back_values, [[0, 1], [2, 3], [4, 5]]);
+    }
+
+    #[test]
+    fn test_add_late_batches_keeps_them_ordered_by_due() {
+        let now = Instant::now();
+        let mut first_generator = DisorderGenerator::new(&disorder(), 0);
+        let mut second_generator = DisorderGenerator::new(&disorder(), 1);
+
+        first_generator.add_late_batches(VecDeque::from([
+            (now + Duration::from_secs(1), record_batch(0..1)),
+            (now + Duration::from_secs(3), record_batch(2..3)),
+        ]));
+        second_generator.add_late_batches(VecDeque::from([(
+            now + Duration::from_secs(2),
+            record_batch(1..2),
+        )]));
+
+        second_generator.add_late_batches(first_generator.take_late_batches());
+
+        assert!(first_generator.held_back_batches().is_empty());
+        let held_back_values: Vec<Vec<i32>> = second_generator
+            .held_back_batches()
+            .iter()
+            .map(|(record_batch, _kind)| values(record_batch))
+            .collect();
+        assert_eq!(held_back_values, [[0], [1], [2]]);
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+
+use arrow::array::{Array, AsArray, BooleanArray, RecordBatch};
+use arrow::compute;
+use arrow::datatypes::{ArrowPrimitiveType, SchemaRef};
+use modelardb_types::types::{ArrowValue, TimestampArray};
+use serde::{Deserialize, Serialize};
+
+use crate::error::DemoError;
+use crate::util::Random;
+
+/// The number of microseconds in a second, the unit of the timestamps.
+const MICROSECONDS_PER_SECOND: f64 = 1_000_000.0;
+
+/// The stream of random numbers the dropouts of each edge node are drawn from.
+const DROPOUT_STREAM: u64 = 0x85eb_ca6b;
+
+/// Which field values ingested into the edge nodes go missing, like sensors that go dark for a
+/// while.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(super) struct Dropout {
+    pub(super) fields: Vec<FieldDropout>,
+    /// Seed for the dropouts so a run can be repeated. Defaults to the current time.
+    pub(super) seed: Option<u64>,
+}
+
+/// Dropouts of the sensor for the field column `field` in each turbine, which start each second
+/// with the probability `probability` and last `burst_seconds`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(super) struct FieldDropout {
+    pub(super) field: String,
+    pub(super) probability: f64,
+    pub(super) burst_seconds: u64,
+    #[serde(default)]
+    pub(super) mode: DropoutMode,
+}
+
+/// What happens to the rows of a turbine while the sensor for a field is dropped out.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum DropoutMode {
+    /// The values of the field are null, which requires a table with nullable field columns.
+    #[default]
+    Null,
+    /// The rows are removed, so all of the fields of the turbine have a gap.
+    Remove,
+}
+
+impl Dropout {
+    /// Check that the fields exist in the table with `table_schema` and that the values can only be
+    /// nulled if its field columns are nullable.
+    pub(super) fn check(&self, table_schema: &SchemaRef) -> Result<(), DemoError> {
+        for field_dropout in &self.fields {
+            let field = table_schema
+                .field_with_name(&field_dropout.field)
+                .ok()
+                .filter(|field| *field.data_type() == ArrowValue::DATA_TYPE)
+                .ok_or_else(|| {
+                    DemoError::Configuration(format!(
+                        "The table has no field column {} to drop out.",
+                        field_dropout.field
+                    ))
+                })?;
+
+            if matches!(field_dropout.mode, DropoutMode::Null) && !field.is_nullable() {
+                return Err(DemoError::Configuration(format!(
+                    "The values of {} can only be nulled if the table is created with nullable \
+                     fields.",
+                    field_dropout.field
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The dropout of a field in a turbine.
+#[derive(Default)]
+struct SensorState {
+    last_timestamp: Option<i64>,
+    /// The timestamp the current dropout ends at, if the sensor is dropped out.
+    dropped_until: Option<i64>,
+}
+
+/// Applies the dropouts to the rows ingested into an edge node.
+pub(super) struct DropoutGenerator {
+    dropout: Dropout,
+    /// Schema of the table, which the rows are converted to so they match its nullability.
+    table_schema: SchemaRef,
+    random: Random,
+    /// The state of each sensor by the position of its field, the park ID, and the windmill ID.
+    sensors: HashMap<(usize, String, String), SensorState>,
+}
+
+impl DropoutGenerator {
+    /// Create a generator for the edge node at `node_index` that ingests into the table with
+    /// `table_schema`.
+    pub(super) fn new(dropout: &Dropout, table_schema: SchemaRef, node_index: usize) -> Self {
+        let random = Random::for_node(dropout.seed, node_index, DROPOUT_STREAM);
+
+        Self {
+            dropout: dropout.clone(),
+            table_schema,
+            random,
+            sensors: HashMap::new(),
+        }
+    }
+
+    /// Return `record_batch`, which has the columns of the table, with the values of the dropped
+    /// out sensors nulled or their rows removed.
+    pub(super) fn apply(&mut self, record_batch: RecordBatch) -> Result<RecordBatch, DemoError> {
+        let mut columns = record_batch.columns().to_vec();
+        let mut removed: Option<BooleanArray> = None;
+
+        for field_dropout in &self.dropout.fields {
+            let index = self.table_schema.index_of(&field_dropout.field)?;
+            let dropped = dropped_rows(
+                &mut self.random,
+                &mut self.sensors,
+                &record_batch,
+                index,
+                field_dropout,
+            )?;
+
+            match field_dropout.mode {
+                DropoutMode::Null => columns[index] = compute::nullif(&columns[index], &dropped)?,
+                DropoutMode::Remove => {
+                    removed = Some(match removed {
+                        Some(removed) => compute::or(&removed, &dropped)?,
+                        None => dropped,
+                    })
+                }
+            }
+        }
+
+        let record_batch = RecordBatch::try_new(self.table_schema.clone(), columns)?;
+
+        match removed {
+            Some(removed) => Ok(compute::filter_record_batch(
+                &record_batch,
+                &compute::not(&removed)?,
+            )?),
+            None => Ok(record_batch),
+        }
+    }
+}
+
+/// Return which rows of `record_batch` are from a turbine whose sensor for the field at `index` is
+/// dropped out according to `sensors`, starting new dropouts as time passes between the rows.
+fn dropped_rows(
+    random: &mut Random,
+    sensors: &mut HashMap<(usize, String, String), SensorState>,
+    record_batch: &RecordBatch,
+    index: usize,
+    field_dropout: &FieldDropout,
+) -> Result<BooleanArray, DemoError> {
+    let timestamps = record_batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<TimestampArray>()
+        .ok_or_else(|| DemoError::Schema("The first column must be timestamps.".to_owned()))?;
+
+    let park_ids = record_batch.column(1).as_string::<i32>();
+    let windmill_ids = record_batch.column(2).as_string::<i32>();
+
+    let burst = field_dropout.burst_seconds as i64 * MICROSECONDS_PER_SECOND as i64;
+
+    let dropped = (0..record_batch.num_rows())
+        .map(|row| {
+            let timestamp = timestamps.value(row);
+            let sensor = sensors
+                .entry((
+                    index,
+                    park_ids.value(row).to_owned(),
+                    windmill_ids.value(row).to_owned(),
+                ))
+                .or_default();
+
+            // The probability is per second, so it is scaled to the time since the last row.
+            let elapsed_seconds = sensor.last_timestamp.map_or(0.0, |last_timestamp| {
+                (timestamp - last_timestamp).max(0) as f64 / MICROSECONDS_PER_SECOND
+            });
+            sensor.last_timestamp = Some(timestamp);
+
+            if sensor
+                .dropped_until
+                .is_some_and(|dropped_until| timestamp >= dropped_until)
+            {
+                sensor.dropped_until = None;
+            }
+
+            let start_probability = 1.0 - (1.0 - field_dropout.probability).powf(elapsed_seconds);
+            if sensor.dropped_until.is_none() && random.next_f64() < start_probability {
+                sensor.dropped_until = Some(timestamp + burst);
+            }
+
+            Some(sensor.dropped_until.is_some())
+        })
+        .collect();
+
+    Ok(dropped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, PrimitiveArray, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use modelardb_types::types::ArrowTimestamp;
+
+    fn table_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("timestamp", ArrowTimestamp::DATA_TYPE, false),
+            Field::new("park_id", DataType::Utf8, false),
+            Field::new("windmill_id", DataType::Utf8, false),
+            Field::new("pitch", ArrowValue::DATA_TYPE, true),
+            Field::new("power", ArrowValue::DATA_TYPE, false),
+        ]))
+    }
+
+    /// Return a batch with a row for each of the `seconds` for each of the `windmill_ids`.
+    fn record_batch(seconds: &[f64], windmill_ids: &[&str]) -> RecordBatch {
+        let rows: Vec<(i64, &str)> = seconds
+            .iter()
+            .flat_map(|second| {
+                let timestamp = (second * MICROSECONDS_PER_SECOND) as i64;
+                windmill_ids
+                    .iter()
+                    .map(move |windmill_id| (timestamp, *windmill_id))
+            })
+            .collect();
+
+        let values: Vec<f32> = (0..rows.len()).map(|row| row as f32).collect();
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(TimestampArray::from_iter_values(
+                rows.iter().map(|(timestamp, _windmill_id)| *timestamp),
+            )),
+            Arc::new(StringArray::from(vec!["park_1"; rows.len()])),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|(_timestamp, windmill_id)| *windmill_id),
+            )),
+            Arc::new(PrimitiveArray::<ArrowValue>::from(values.clone())),
+            Arc::new(PrimitiveArray::<ArrowValue>::from(values)),
+        ];
+
+        RecordBatch::try_new(table_schema(), columns).unwrap()
+    }
+
+    fn dropout(field: &str, probability: f64, mode: DropoutMode) -> Dropout {
+        Dropout {
+            fields: vec![FieldDropout {
+                field: field.to_owned(),
+                probability,
+                burst_seconds: 1,
+                mode,
+            }],
+            seed: Some(1),
+        }
+    }
+
+    #[test]
+    fn test_check_nullable_field() {
+        let table_schema = table_schema();
+
+        assert!(dropout("pitch", 0.1, DropoutMode::Null)
+            .check(&table_schema)
+            .is_ok());
+        assert!(dropout("power", 0.1, DropoutMode::Remove)
+            .check(&table_schema)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_check_fails_for_null_in_field_that_is_not_nullable() {
+        assert!(dropout("power", 0.1, DropoutMode::Null)
+            .check(&table_schema())
+            .is_err());
+    }
+
+    #[test]
+    fn test_check_fails_for_columns_that_are_not_fields() {
+        let table_schema = table_schema();
+
+        assert!(dropout("park_id", 0.1, DropoutMode::Remove)
+            .check(&table_schema)
+            .is_err());
+        assert!(dropout("missing", 0.1, DropoutMode::Remove)
+            .check(&table_schema)
+            .is_err());
+    }
+
+    #[test]
+    fn test_apply_without_dropouts() {
+        let dropout = dropout("pitch", 0.0, DropoutMode::Null);
+        let mut dropout_generator = DropoutGenerator::new(&dropout, table_schema(), 0);
+
+        let record_batch = record_batch(&[0.0, 0.5, 1.0, 2.0], &["windmill_1"]);
+        let applied = dropout_generator.apply(record_batch.clone()).unwrap();
+
+        assert_eq!(applied, record_batch);
+    }
+
+    #[test]
+    fn test_apply_nulls_values_of_dropped_out_sensors() {
+        let dropout = dropout("pitch", 1.0, DropoutMode::Null);
+        let mut dropout_generator = DropoutGenerator::new(&dropout, table_schema(), 0);
+
+        let record_batch = record_batch(&[0.0, 0.5, 1.0], &["windmill_1", "windmill_2"]);
+        let applied = dropout_generator.apply(record_batch).unwrap();
+
+        // A dropout can only start when time has passed since the previous row of the turbine.
+        let pitch = applied.column(3);
+        assert_eq!(pitch.null_count(), 4);
+        assert!(pitch.is_valid(0) && pitch.is_valid(1));
+        assert_eq!(applied.column(4).null_count(), 0);
+    }
+
+    #[test]
+    fn test_apply_removes_rows_of_dropped_out_sensors() {
+        let dropout = dropout("pitch", 1.0, DropoutMode::Remove);
+        let mut dropout_generator = DropoutGenerator::new(&dropout, table_schema(), 0);
+
+        let first_batch = record_batch(&[0.0], &["windmill_1"]);
+        assert_eq!(dropout_generator.apply(first_batch).unwrap().num_rows(), 1);
+
+        // The dropout continues across batches until it has lasted for its burst.
+        let second_batch = record_batch(&[0.5, 1.0], &["windmill_1"]);
+        assert_eq!(dropout_generator.apply(second_batch).unwrap().num_rows(), 0);
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt::{self, Display, Formatter};
+
+use arrow::error::ArrowError;
+use datafusion::parquet::errors::ParquetError;
+use serde::Serialize;
+
+/// Error returned by the Tauri commands and reported by the background tasks. It is serialized to
+/// the frontend as an object with the `kind` of error and a `message` describing it.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub(super) enum DemoError {
+    /// A node, the ModelarDB manager, or Docker could not be connected to.
+    Connection(String),
+    /// A node responded to an Apache Arrow Flight request with an error status.
+    FlightStatus(String),
+    /// The object store could not be read.
+    ObjectStore(String),
+    /// Data could not be read, converted, or did not match the expected schema.
+    Schema(String),
+    /// The utility service for running Python scripts failed.
+    ScriptRunner(String),
+    /// A container in the cluster could not be managed through Docker.
+    Docker(String),
+    /// The nodes file, storage configuration, or command arguments are invalid.
+    Configuration(String),
+}
+
+impl Display for DemoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connection(message) => write!(f, "Connection error: {message}"),
+            Self::FlightStatus(message) => write!(f, "Flight status error: {message}"),
+            Self::ObjectStore(message) => write!(f, "Object store error: {message}"),
+            Self::Schema(message) => write!(f, "Schema error: {message}"),
+            Self::ScriptRunner(message) => write!(f, "Script runner error: {message}"),
+            Self::Docker(message) => write!(f, "Docker error: {message}"),
+            Self::Configuration(message) => write!(f, "Configuration error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for DemoError {}
+
+impl From<tonic::transport::Error> for DemoError {
+    fn from(error: tonic::transport::Error) -> Self {
+        Self::Connection(error.to_string())
+    }
+}
+
+impl From<tonic::Status> for DemoError {
+    fn from(status: tonic::Status) -> Self {
+        Self::FlightStatus(status.message().to_owned())
+    }
+}
+
+impl From<object_store::Error> for DemoError {
+    fn from(error: object_store::Error) -> Self {
+        Self::ObjectStore(error.to_string())
+    }
+}
+
+impl From<ArrowError> for DemoError {
+    fn from(error: ArrowError) -> Self {
+        Self::Schema(error.to_string())
+    }
+}
+
+impl From<ParquetError> for DemoError {
+    fn from(error: ParquetError) -> Self {
+        Self::Schema(error.to_string())
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use modelardb_types::types::ErrorBound;
+use serde::{Deserialize, Serialize};
+
+use crate::error::DemoError;
+
+/// The error bound ModelarDB compresses the values of a field column within. A bound of 0 is
+/// lossless.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub(super) enum ColumnErrorBound {
+    /// The values may differ from the ingested values by at most this amount.
+    Absolute(f32),
+    /// The values may differ from the ingested values by at most this percentage of them.
+    Relative(f32),
+}
+
+impl Default for ColumnErrorBound {
+    fn default() -> Self {
+        Self::Absolute(0.0)
+    }
+}
+
+/// The error bounds of the field columns of the time series table, e.g., an absolute bound of
+/// 0.01 for the directions given as cosine and sine and a relative bound of 1% for the power.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(super) struct ErrorBounds {
+    /// The error bound of the field columns that are not in `columns`. Defaults to lossless.
+    pub(super) default: ColumnErrorBound,
+    /// The error bound of each field column by its name.
+    pub(super) columns: HashMap<String, ColumnErrorBound>,
+}
+
+/// The error bound that was chosen for a field column when the table was created.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(super) struct FieldErrorBound {
+    pub(super) name: String,
+    pub(super) error_bound: ColumnErrorBound,
+}
+
+impl ErrorBounds {
+    /// Create error bounds with `error_bound` for all field columns.
+    pub(super) fn uniform(error_bound: ColumnErrorBound) -> Self {
+        Self {
+            default: error_bound,
+            columns: HashMap::new(),
+        }
+    }
+
+    /// Return the error bound of each of the field columns in `field_column_names` in the same
+    /// order. Fails if a column that is not a field column has an error bound.
+    pub(super) fn try_resolve(
+        &self,
+        field_column_names: &[String],
+    ) -> Result<Vec<FieldErrorBound>, DemoError> {
+        if let Some(name) = self
+            .columns
+            .keys()
+            .find(|name| !field_column_names.contains(name))
+        {
+            return Err(DemoError::Configuration(format!(
+                "The table has no field column {name} to set an error bound for."
+            )));
+        }
+
+        let field_error_bounds = field_column_names
+            .iter()
+            .map(|name| FieldErrorBound {
+                name: name.clone(),
+                error_bound: self.columns.get(name).copied().unwrap_or(self.default),
+            })
+            .collect();
+
+        Ok(field_error_bounds)
+    }
+}
+
+/// Return the error bounds in `field_error_bounds` by field column as used by ModelarDB. Fails if
+/// a bound is not valid in ModelarDB, e.g., because it is negative or a relative bound is above
+/// 100%.
+pub(super) fn try_modelardb_error_bounds(
+    field_error_bounds: &[FieldErrorBound],
+) -> Result<HashMap<String, ErrorBound>, DemoError> {
+    field_error_bounds
+        .iter()
+        .map(|FieldErrorBound { name, error_bound }| {
+            let modelardb_error_bound = match *error_bound {
+                ColumnErrorBound::Absolute(value) => ErrorBound::try_new_absolute(value),
+                ColumnErrorBound::Relative(value) => ErrorBound::try_new_relative(value),
+            }
+            .map_err(|error| {
+                DemoError::Configuration(format!("The error bound of {name} is invalid: {error}"))
+            })?;
+
+            Ok((name.clone(), modelardb_error_bound))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field_column_names() -> Vec<String> {
+        vec!["cos_wind_dir".to_owned(), "active_power".to_owned()]
+    }
+
+    fn field_error_bound(name: &str, error_bound: ColumnErrorBound) -> FieldErrorBound {
+        FieldErrorBound {
+            name: name.to_owned(),
+            error_bound,
+        }
+    }
+
+    #[test]
+    fn test_try_resolve_uses_default_for_other_columns() {
+        let error_bounds = ErrorBounds {
+            default: ColumnErrorBound::Absolute(0.01),
+            columns: HashMap::from([("active_power".to_owned(), ColumnErrorBound::Relative(1.0))]),
+        };
+
+        let field_error_bounds = error_bounds.try_resolve(&field_column_names()).unwrap();
+
+        assert_eq!(field_error_bounds.len(), 2);
+        assert_eq!(field_error_bounds[0].name, "cos_wind_dir");
+        assert!(matches!(
+            field_error_bounds[0].error_bound,
+            ColumnErrorBound::Absolute(value) if value == 0.01
+        ));
+        assert_eq!(field_error_bounds[1].name, "active_power");
+        assert!(matches!(
+            field_error_bounds[1].error_bound,
+            ColumnErrorBound::Relative(value) if value == 1.0
+        ));
+    }
+
+    #[test]
+    fn test_try_resolve_defaults_to_lossless() {
+        let field_error_bounds = ErrorBounds::default()
+            .try_resolve(&field_column_names())
+            .unwrap();
+
+        assert!(field_error_bounds.iter().all(|field_error_bound| matches!(
+            field_error_bound.error_bound,
+            ColumnErrorBound::Absolute(value) if value == 0.0
+        )));
+    }
+
+    #[test]
+    fn test_try_resolve_fails_for_unknown_column() {
+        let error_bounds = ErrorBounds {
+            default: ColumnErrorBound::default(),
+            columns: HashMap::from([("pitch".to_owned(), ColumnErrorBound::Absolute(1.0))]),
+        };
+
+        assert!(matches!(
+            error_bounds.try_resolve(&field_column_names()),
+            Err(DemoError::Configuration(_))
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_error_bounds() {
+        let error_bounds: ErrorBounds = serde_json::from_str(
+            r#"{ "columns": { "active_power": { "type": "relative", "value": 0.5 } } }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(error_bounds.default, ColumnErrorBound::Absolute(value) if value == 0.0));
+        assert!(matches!(
+            error_bounds.columns["active_power"],
+            ColumnErrorBound::Relative(value) if value == 0.5
+        ));
+    }
+
+    #[test]
+    fn test_try_modelardb_error_bounds() {
+        let modelardb_error_bounds = try_modelardb_error_bounds(&[
+            field_error_bound("cos_wind_dir", ColumnErrorBound::Absolute(0.01)),
+            field_error_bound("active_power", ColumnErrorBound::Relative(100.0)),
+        ])
+        .unwrap();
+
+        assert_eq!(modelardb_error_bounds.len(), 2);
+        assert!(modelardb_error_bounds.contains_key("cos_wind_dir"));
+        assert!(modelardb_error_bounds.contains_key("active_power"));
+    }
+
+    #[test]
+    fn test_try_modelardb_error_bounds_fails_for_negative_bounds() {
+        for error_bound in [
+            ColumnErrorBound::Absolute(-1.0),
+            ColumnErrorBound::Relative(-1.0),
+        ] {
+            let result =
+                try_modelardb_error_bounds(&[field_error_bound("active_power", error_bound)]);
+            assert!(matches!(result, Err(DemoError::Configuration(_))));
+        }
+    }
+
+    #[test]
+    fn test_try_modelardb_error_bounds_fails_for_relative_bound_above_100() {
+        let result = try_modelardb_error_bounds(&[field_error_bound(
+            "active_power",
+            ColumnErrorBound::Relative(100.1),
+        )]);
+
+        assert!(matches!(result, Err(DemoError::Configuration(_))));
+    }
+}
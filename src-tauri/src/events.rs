@@ -0,0 +1,83 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::error::DemoError;
+use crate::session::SessionRecorder;
+
+/// Destination of the events emitted while running the demo, i.e., the frontend when running the
+/// Tauri app and standard output or a file when running the command line interface.
+pub(super) trait EventEmitter: Clone + Send + Sync + 'static {
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S);
+
+    /// The recorder the requests to the cluster and the events are recorded with, if any.
+    fn session_recorder(&self) -> Option<Arc<SessionRecorder>> {
+        None
+    }
+}
+
+impl<R: Runtime> EventEmitter for AppHandle<R> {
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        if let Some(session_recorder) = self.session_recorder() {
+            session_recorder.record_event(event, &payload);
+        }
+
+        // The demo keeps running if the frontend cannot be reached, e.g., while the window closes.
+        if self.emit(event, payload).is_err() {
+            if let Some(failed_emits) = self.try_state::<FailedEmits>() {
+                failed_emits.increment();
+            }
+        }
+    }
+
+    fn session_recorder(&self) -> Option<Arc<SessionRecorder>> {
+        self.try_state::<Arc<SessionRecorder>>()
+            .map(|session_recorder| session_recorder.inner().clone())
+    }
+}
+
+/// The number of events that could not be emitted to the frontend, as returned by `failed_emits`.
+#[derive(Default)]
+pub(super) struct FailedEmits(AtomicU64);
+
+impl FailedEmits {
+    fn increment(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn count(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct TaskError {
+    task: String,
+    error: DemoError,
+}
+
+/// Run `future` and emit a `task-error` event with the name of the `task` if it fails, so failures
+/// in background tasks are reported instead of being lost when the task ends.
+pub(super) async fn report_task_error<E: EventEmitter>(
+    emitter: E,
+    task: &str,
+    future: impl Future<Output = Result<(), DemoError>>,
+) {
+    if let Err(error) = future.await {
+        emit_task_error(&emitter, task, error);
+    }
+}
+
+/// Emit a `task-error` event with the name of the `task` that failed with `error`.
+pub(super) fn emit_task_error<E: EventEmitter>(emitter: &E, task: &str, error: DemoError) {
+    emitter.emit_event(
+        "task-error",
+        TaskError {
+            task: task.to_owned(),
+            error,
+        },
+    );
+}
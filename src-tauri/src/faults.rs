@@ -0,0 +1,280 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use arrow::array::{Array, RecordBatch};
+use arrow::compute;
+use modelardb_types::types::TimestampArray;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tokio::time;
+
+use crate::cluster;
+use crate::error::DemoError;
+use crate::events::{self, EventEmitter};
+use crate::topology::Topology;
+
+/// How often the containers are paused or unpaused to follow the fault schedule.
+const CONTAINER_FAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How a node misbehaves while a fault is active.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum FaultKind {
+    /// Writes to the node are dropped without being sent to it.
+    DropWrites,
+    /// Each write and flush waits `latency_ms` before it is sent to the node.
+    Latency { latency_ms: u64 },
+    /// Writes and flushes fail with an Apache Arrow Flight error without being sent to the node.
+    FlightError,
+    /// The container of the node is paused, so requests to it fail or time out.
+    PauseContainer,
+}
+
+impl FaultKind {
+    fn applies_to(&self, operation: Operation) -> bool {
+        !matches!((self, operation), (Self::DropWrites, Operation::Flush))
+    }
+}
+
+/// A fault in the edge or comparison node with `url` that starts `start_seconds` after the fault
+/// schedule is set and lasts for `duration_seconds`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(super) struct Fault {
+    pub(super) url: String,
+    pub(super) kind: FaultKind,
+    pub(super) start_seconds: u64,
+    pub(super) duration_seconds: u64,
+}
+
+impl Fault {
+    fn is_active(&self, elapsed: Duration) -> bool {
+        let start = Duration::from_secs(self.start_seconds);
+        elapsed >= start && elapsed < start + Duration::from_secs(self.duration_seconds)
+    }
+}
+
+/// The requests to the nodes that faults are injected into.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum Operation {
+    Write,
+    Flush,
+}
+
+/// A request to a node that was affected by a fault, including the rows it wrote, so it can be
+/// checked afterwards which rows are missing from or delayed in the node.
+#[derive(Clone, Debug, Serialize)]
+pub(super) struct AffectedRequest {
+    url: String,
+    fault: FaultKind,
+    operation: Operation,
+    /// Milliseconds since the fault schedule was set.
+    elapsed_ms: u128,
+    rows: usize,
+    first_timestamp: Option<i64>,
+    last_timestamp: Option<i64>,
+}
+
+struct FaultSchedule {
+    faults: Vec<Fault>,
+    start: Instant,
+    affected_requests: Vec<AffectedRequest>,
+    /// URLs of the nodes whose containers are currently paused by a fault.
+    paused_urls: HashSet<String>,
+}
+
+/// Injects the faults in the fault schedule into the requests to the nodes and records the requests
+/// that were affected.
+pub(super) struct FaultInjector {
+    schedule: Mutex<FaultSchedule>,
+}
+
+impl Default for FaultInjector {
+    fn default() -> Self {
+        Self {
+            schedule: Mutex::new(FaultSchedule {
+                faults: vec![],
+                start: Instant::now(),
+                affected_requests: vec![],
+                paused_urls: HashSet::new(),
+            }),
+        }
+    }
+}
+
+impl FaultInjector {
+    /// Replace the fault schedule with `faults`, which start relative to now, and clear the
+    /// affected requests. The containers paused by the previous schedule are still paused until
+    /// [`pause_containers`] unpauses the ones that `faults` does not pause.
+    pub(super) fn set_faults(&self, faults: Vec<Fault>) {
+        let mut schedule = self.schedule.lock().unwrap();
+
+        schedule.faults = faults;
+        schedule.start = Instant::now();
+        schedule.affected_requests.clear();
+    }
+
+    /// Return the requests that were affected by a fault since the fault schedule was set.
+    pub(super) fn affected_requests(&self) -> Vec<AffectedRequest> {
+        self.schedule.lock().unwrap().affected_requests.clone()
+    }
+
+    /// Inject the faults that are active for the node with `url` into an `operation` with the
+    /// rows in `record_batch`, if any. Returns [`false`] if the request should not be sent to the
+    /// node, and an error if it should fail.
+    pub(super) async fn inject<E: EventEmitter>(
+        &self,
+        emitter: &E,
+        url: &str,
+        operation: Operation,
+        record_batch: Option<&RecordBatch>,
+    ) -> Result<bool, DemoError> {
+        let (active_faults, affected_requests) = {
+            let mut schedule = self.schedule.lock().unwrap();
+            let elapsed = schedule.start.elapsed();
+
+            let active_faults: Vec<FaultKind> = schedule
+                .faults
+                .iter()
+                .filter(|fault| fault.url == url && fault.is_active(elapsed))
+                .map(|fault| fault.kind)
+                .filter(|kind| kind.applies_to(operation))
+                .collect();
+
+            let (first_timestamp, last_timestamp) =
+                record_batch.map(timestamp_range).unwrap_or_default();
+
+            let affected_requests: Vec<AffectedRequest> = active_faults
+                .iter()
+                .map(|fault| AffectedRequest {
+                    url: url.to_owned(),
+                    fault: *fault,
+                    operation,
+                    elapsed_ms: elapsed.as_millis(),
+                    rows: record_batch.map_or(0, |record_batch| record_batch.num_rows()),
+                    first_timestamp,
+                    last_timestamp,
+                })
+                .collect();
+
+            schedule
+                .affected_requests
+                .extend(affected_requests.iter().cloned());
+
+            (active_faults, affected_requests)
+        };
+
+        for affected_request in affected_requests {
+            emitter.emit_event("request-affected", affected_request);
+        }
+
+        let mut send = true;
+        for fault in active_faults {
+            match fault {
+                FaultKind::DropWrites => send = false,
+                FaultKind::Latency { latency_ms } => {
+                    time::sleep(Duration::from_millis(latency_ms)).await
+                }
+                FaultKind::FlightError => {
+                    return Err(DemoError::FlightStatus(format!("Injected fault in {url}.")))
+                }
+                FaultKind::PauseContainer => (),
+            }
+        }
+
+        Ok(send)
+    }
+
+    /// Return the URLs of the nodes whose containers should be paused or unpaused to follow the
+    /// fault schedule and if they should be paused.
+    fn container_changes(&self) -> Vec<(String, bool)> {
+        let schedule = self.schedule.lock().unwrap();
+        let elapsed = schedule.start.elapsed();
+
+        let should_be_paused: HashSet<&String> = schedule
+            .faults
+            .iter()
+            .filter(|fault| {
+                matches!(fault.kind, FaultKind::PauseContainer) && fault.is_active(elapsed)
+            })
+            .map(|fault| &fault.url)
+            .collect();
+
+        let pause = should_be_paused
+            .iter()
+            .filter(|url| !schedule.paused_urls.contains(url.as_str()))
+            .map(|url| ((*url).clone(), true));
+
+        let unpause = schedule
+            .paused_urls
+            .iter()
+            .filter(|url| !should_be_paused.contains(url))
+            .map(|url| (url.clone(), false));
+
+        pause.chain(unpause).collect()
+    }
+
+    fn set_paused(&self, url: &str, paused: bool) {
+        let mut schedule = self.schedule.lock().unwrap();
+
+        if paused {
+            schedule.paused_urls.insert(url.to_owned());
+        } else {
+            schedule.paused_urls.remove(url);
+        }
+    }
+}
+
+/// Return the first and last timestamp of the rows in `record_batch`, which has the columns of the
+/// table.
+fn timestamp_range(record_batch: &RecordBatch) -> (Option<i64>, Option<i64>) {
+    match record_batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<TimestampArray>()
+    {
+        Some(timestamps) => (compute::min(timestamps), compute::max(timestamps)),
+        None => (None, None),
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct ContainerPaused {
+    url: String,
+    paused: bool,
+}
+
+/// Pause and unpause the containers of the nodes in `topology_receiver` to follow the fault
+/// schedule of `fault_injector` and emit a `container-paused` event each time a container is
+/// paused or unpaused.
+pub(super) async fn inject_container_faults_task<E: EventEmitter>(
+    emitter: E,
+    topology_receiver: watch::Receiver<Topology>,
+    fault_injector: Arc<FaultInjector>,
+) {
+    loop {
+        let topology = topology_receiver.borrow().clone();
+        pause_containers(&emitter, &topology, &fault_injector).await;
+
+        time::sleep(CONTAINER_FAULT_INTERVAL).await;
+    }
+}
+
+/// Pause and unpause the containers of the nodes in `topology` so they match the faults that are
+/// currently active in the fault schedule of `fault_injector`.
+pub(super) async fn pause_containers<E: EventEmitter>(
+    emitter: &E,
+    topology: &Topology,
+    fault_injector: &FaultInjector,
+) {
+    for (url, paused) in fault_injector.container_changes() {
+        match cluster::pause_node(topology, &url, paused).await {
+            Ok(()) => {
+                fault_injector.set_paused(&url, paused);
+                emitter.emit_event("container-paused", ContainerPaused { url, paused });
+            }
+            Err(error) => events::emit_task_error(emitter, crate::FAULT_INJECTION_TASK, error),
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, PrimitiveArray, RecordBatch};
+use arrow::datatypes::{ArrowPrimitiveType, DataType, Field, Schema, SchemaRef};
+use modelardb_types::types::{ArrowTimestamp, ArrowValue, TimestampArray};
+use serde::{Deserialize, Serialize};
+
+use crate::dataset::WIND_FIELD_NAMES;
+use crate::error::DemoError;
+use crate::util::Random;
+
+/// Wind speed in m/s where the turbines start producing power.
+const CUT_IN_WIND_SPEED: f64 = 3.0;
+
+/// Wind speed in m/s where the turbines reach their rated power.
+const RATED_WIND_SPEED: f64 = 12.0;
+
+/// Wind speed in m/s where the turbines are stopped to protect them.
+const CUT_OUT_WIND_SPEED: f64 = 25.0;
+
+/// Power in kW produced by a turbine at or above the rated wind speed.
+const RATED_POWER: f64 = 2000.0;
+
+/// Rotations per minute of the rotor at or above the rated wind speed.
+const RATED_ROTOR_SPEED: f64 = 16.0;
+
+/// Pitch angle in degrees of the blades of a stopped turbine.
+const FEATHERED_PITCH_ANGLE: f64 = 90.0;
+
+/// How fast in degrees per second the nacelle turns to face the wind.
+const YAW_RATE: f64 = 0.5;
+
+/// How fast in 1/s the wind speed returns to its mean.
+const WIND_SPEED_REVERSION: f64 = 1.0 / 300.0;
+
+/// Standard deviation in m/s of the wind speed around its mean.
+const WIND_SPEED_DEVIATION: f64 = 2.0;
+
+/// Standard deviation in degrees of the change in wind direction per second.
+const WIND_DIRECTION_DEVIATION: f64 = 0.5;
+
+/// The stream of random numbers the turbines of each edge node are generated from.
+const GENERATOR_STREAM: u64 = 0x9e37_79b9;
+
+/// The synthetic turbines and how their measurements deviate from the physical model.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub(super) struct GeneratorConfig {
+    /// The number of turbines, which are spread evenly between the edge nodes unless a turbine
+    /// layout is set.
+    pub(super) turbines: usize,
+    /// The wind speed in m/s the wind varies around.
+    pub(super) mean_wind_speed: f64,
+    /// Standard deviation of the measurement noise relative to the measured value, or to a full
+    /// turn for the directions.
+    pub(super) noise: f64,
+    /// How much the wind speed sensors drift in m/s per hour.
+    pub(super) drift_per_hour: f64,
+    /// The probability per second that a turbine that is running has a fault and stops.
+    pub(super) fault_probability: f64,
+    /// How long a fault lasts before the turbine starts again.
+    pub(super) fault_seconds: u64,
+    /// Seed for the generator so the data can be generated again. Defaults to the current time.
+    pub(super) seed: Option<u64>,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            turbines: 4,
+            mean_wind_speed: 8.0,
+            noise: 0.01,
+            drift_per_hour: 0.0,
+            fault_probability: 0.0,
+            fault_seconds: 60,
+            seed: None,
+        }
+    }
+}
+
+/// Schema of the table the synthetic data is ingested into, which is the same as for the bundled
+/// data set.
+pub(super) fn table_schema() -> SchemaRef {
+    let mut fields = vec![
+        Field::new("timestamp", ArrowTimestamp::DATA_TYPE, false),
+        Field::new("park_id", DataType::Utf8, false),
+        Field::new("windmill_id", DataType::Utf8, false),
+    ];
+
+    fields.extend(data_fields());
+
+    Arc::new(Schema::new(fields))
+}
+
+fn data_fields() -> Vec<Field> {
+    WIND_FIELD_NAMES
+        .iter()
+        .map(|name| Field::new(*name, ArrowValue::DATA_TYPE, false))
+        .collect()
+}
+
+/// Simulates the turbines whose data is ingested into one edge node.
+pub(super) struct TurbineGenerator {
+    config: GeneratorConfig,
+    turbines: Vec<Turbine>,
+    random: Random,
+    /// The timestamp of the last generated rows, used to advance the turbines between rows.
+    last_timestamp: Option<i64>,
+}
+
+impl TurbineGenerator {
+    /// Create a generator for `num_turbines` turbines whose data is ingested into the edge node at
+    /// `node_index`.
+    pub(super) fn new(config: &GeneratorConfig, num_turbines: usize, node_index: usize) -> Self {
+        let mut random = Random::for_node(config.seed, node_index, GENERATOR_STREAM);
+
+        let turbines = (0..num_turbines)
+            .map(|_| Turbine::new(config, &mut random))
+            .collect();
+
+        Self {
+            config: config.clone(),
+            turbines,
+            random,
+            last_timestamp: None,
+        }
+    }
+
+    /// Generate the fields of a row for each of the turbines at each of `timestamps`. The rows are
+    /// ordered by timestamp and then by turbine, so the turbines are interleaved.
+    pub(super) fn next_data_points(
+        &mut self,
+        timestamps: &TimestampArray,
+    ) -> Result<RecordBatch, DemoError> {
+        let num_rows = timestamps.len() * self.turbines.len();
+
+        let mut fields: Vec<Vec<f32>> = WIND_FIELD_NAMES
+            .iter()
+            .map(|_| Vec::with_capacity(num_rows))
+            .collect();
+
+        for timestamp in timestamps.values() {
+            let elapsed = self
+                .last_timestamp
+                .map_or(0.0, |last_timestamp| {
+                    (timestamp - last_timestamp) as f64 / 1e6
+                })
+                .max(0.0);
+            self.last_timestamp = Some(*timestamp);
+
+            for turbine in &mut self.turbines {
+                turbine.advance(elapsed, &self.config, &mut self.random);
+                let measurements = turbine.measure(self.config.noise, &mut self.random);
+
+                for (field, measurement) in fields.iter_mut().zip(measurements) {
+                    field.push(measurement as f32);
+                }
+            }
+        }
+
+        let columns: Vec<ArrayRef> = fields
+            .into_iter()
+            .map(|field| Arc::new(PrimitiveArray::<ArrowValue>::from(field)) as ArrayRef)
+            .collect();
+
+        let schema = Arc::new(Schema::new(data_fields()));
+        Ok(RecordBatch::try_new(schema, columns)?)
+    }
+}
+
+/// The physical state of a synthetic turbine.
+struct Turbine {
+    /// The wind speed in m/s the wind at this turbine varies around.
+    mean_wind_speed: f64,
+    wind_speed: f64,
+    /// The direction of the wind and the nacelle in degrees.
+    wind_direction: f64,
+    nacelle_direction: f64,
+    /// How much the wind speed sensor currently deviates from the actual wind speed.
+    drift: f64,
+    /// Seconds until the turbine starts again after a fault.
+    fault_seconds_left: f64,
+}
+
+impl Turbine {
+    fn new(config: &GeneratorConfig, random: &mut Random) -> Self {
+        // Turbines in different positions of a park see slightly different winds.
+        let mean_wind_speed = (config.mean_wind_speed + random.next_gaussian()).max(0.0);
+        let wind_direction = random.next_f64() * 360.0;
+
+        Self {
+            mean_wind_speed,
+            wind_speed: mean_wind_speed,
+            wind_direction,
+            nacelle_direction: wind_direction,
+            drift: 0.0,
+            fault_seconds_left: 0.0,
+        }
+    }
+
+    /// Advance the state of the turbine by `elapsed` seconds.
+    fn advance(&mut self, elapsed: f64, config: &GeneratorConfig, random: &mut Random) {
+        // The wind speed follows an Ornstein-Uhlenbeck process around its mean.
+        let volatility = WIND_SPEED_DEVIATION * (2.0 * WIND_SPEED_REVERSION).sqrt();
+        self.wind_speed +=
+            WIND_SPEED_REVERSION * (self.mean_wind_speed - self.wind_speed) * elapsed
+                + volatility * elapsed.sqrt() * random.next_gaussian();
+        self.wind_speed = self.wind_speed.max(0.0);
+
+        self.wind_direction = (self.wind_direction
+            + WIND_DIRECTION_DEVIATION * elapsed.sqrt() * random.next_gaussian())
+        .rem_euclid(360.0);
+
+        // The nacelle turns towards the wind at a limited rate.
+        let yaw_error =
+            (self.wind_direction - self.nacelle_direction + 540.0).rem_euclid(360.0) - 180.0;
+        let max_yaw = YAW_RATE * elapsed;
+        self.nacelle_direction =
+            (self.nacelle_direction + yaw_error.clamp(-max_yaw, max_yaw)).rem_euclid(360.0);
+
+        self.drift += config.drift_per_hour * elapsed / 3600.0;
+
+        if self.fault_seconds_left > 0.0 {
+            self.fault_seconds_left = (self.fault_seconds_left - elapsed).max(0.0);
+        } else if random.next_f64() < 1.0 - (1.0 - config.fault_probability).powf(elapsed) {
+            self.fault_seconds_left = config.fault_seconds as f64;
+        }
+    }
+
+    /// Return the measurements of the turbine in the order of [`WIND_FIELD_NAMES`] with `noise`
+    /// relative to the measured values, or to a full turn for the directions.
+    fn measure(&self, noise: f64, random: &mut Random) -> [f64; 10] {
+        let running = self.fault_seconds_left == 0.0
+            && self.wind_speed >= CUT_IN_WIND_SPEED
+            && self.wind_speed < CUT_OUT_WIND_SPEED;
+
+        let (pitch_angle, rotor_speed, active_power) = if running {
+            let rotor_speed =
+                (RATED_ROTOR_SPEED * self.wind_speed / RATED_WIND_SPEED).min(RATED_ROTOR_SPEED);
+
+            // The power curve grows with the cube of the wind speed until the rated power, after
+            // which the blades are pitched to keep the power constant.
+            if self.wind_speed < RATED_WIND_SPEED {
+                let active_power = RATED_POWER
+                    * (self.wind_speed.powi(3) - CUT_IN_WIND_SPEED.powi(3))
+                    / (RATED_WIND_SPEED.powi(3) - CUT_IN_WIND_SPEED.powi(3));
+
+                (0.0, rotor_speed, active_power)
+            } else {
+                let pitch_angle = 1.5 * (self.wind_speed - RATED_WIND_SPEED);
+                (pitch_angle, rotor_speed, RATED_POWER)
+            }
+        } else {
+            (FEATHERED_PITCH_ANGLE, 0.0, 0.0)
+        };
+
+        let mut measure = |value: f64| value * (1.0 + noise * random.next_gaussian());
+
+        let wind_speed = measure(self.wind_speed + self.drift).max(0.0);
+        let pitch_angle = measure(pitch_angle);
+        let rotor_speed = measure(rotor_speed).max(0.0);
+        let active_power = measure(active_power);
+
+        // The noise of the directions is added, so it does not depend on where north is.
+        let mut measure_direction =
+            |direction: f64| (direction + noise * 360.0 * random.next_gaussian()).rem_euclid(360.0);
+
+        let nacelle_direction = measure_direction(self.nacelle_direction);
+        let wind_direction = measure_direction(self.wind_direction);
+
+        // The sine and cosine are computed from the measured directions so they stay consistent.
+        [
+            wind_speed,
+            pitch_angle,
+            rotor_speed,
+            active_power,
+            nacelle_direction.to_radians().cos(),
+            nacelle_direction.to_radians().sin(),
+            wind_direction.to_radians().cos(),
+            wind_direction.to_radians().sin(),
+            nacelle_direction,
+            wind_direction,
+        ]
+    }
+}
@@ -0,0 +1,590 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::FutureExt;
+use object_store::ObjectStore;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::{watch, Notify};
+use tokio::time;
+
+use crate::dataset::{ColumnMapping, DataSource, Dataset};
+use crate::error::DemoError;
+use crate::error_bounds::{ColumnErrorBound, ErrorBounds};
+use crate::events::EventEmitter;
+use crate::faults::{self, FaultInjector};
+use crate::layout::TurbineLayout;
+use crate::rate::{IngestionRate, RateGenerator};
+use crate::replay::ReplaySpeed;
+use crate::session::{self, SessionRecorder};
+use crate::storage::StorageConfig;
+use crate::supervisor::Supervisor;
+use crate::topology::Topology;
+use crate::verification::{self, WrittenRows};
+use crate::writer::{ComparisonFormat, WriteOptions};
+use crate::{discovery, health};
+
+const USAGE: &str = "Usage: modelardb-demo-cli [OPTIONS]
+
+Options:
+  --nodes <FILE>          Nodes file with the cluster topology [default: data/nodes.json]
+  --data <FILE>           Parquet, CSV, or Arrow IPC file with the data set [default: data/wind_cleaned.parquet]
+  --mapping <FILE>        JSON file with the column mapping of the data set [default: the bundled mapping]
+  --synthetic <FILE>      JSON file with the synthetic turbines to generate data for, overrides --data
+  --layout <FILE>         JSON file with the parks and turbines of each edge node [default: spread evenly]
+  --duration <SECONDS>    How long to ingest data for [default: 60]
+  --passes <PASSES>       Stop earlier when each edge node has read its partition this many times
+  --count <ROWS>          Rows ingested into each edge node per second [default: 4000]
+  --rate <FILE>           JSON file with the ingestion rate profile, overrides --count
+  --ingestion <FILE>      JSON file with the backpressure, disorder, dropout, and passes
+  --faults <FILE>         JSON file with the faults to inject into the nodes during the run
+  --error-bound <BOUND>   Absolute error bound of all field columns of the table [default: 5]
+  --relative-error-bound <PERCENT>
+                          Relative error bound of all field columns of the table, overrides --error-bound
+  --error-bounds <FILE>   JSON file with the error bounds by field column, overrides both of the above
+  --nullable-fields       Create the table with nullable field columns so values can be dropped out
+  --comparison <FORMAT>   File format of the comparison nodes, parquet or orc [default: parquet]
+  --output <FILE>         Write the events to a file instead of standard output
+  --record <DIR>          Record the requests to the nodes and the events into a directory
+  --replay <DIR>          Replay a recorded session at its recorded pace instead of ingesting
+  --reset                 Drop the table and delete all files before running
+  --verify                Compare the written rows to the stored rows at the end of the run
+  --help                  Print this message";
+
+/// Options for a headless run of the demo pipeline.
+pub(super) struct CliOptions {
+    nodes_file: PathBuf,
+    data_file: PathBuf,
+    mapping_file: Option<PathBuf>,
+    synthetic_file: Option<PathBuf>,
+    layout_file: Option<PathBuf>,
+    duration: Duration,
+    passes: Option<usize>,
+    count: usize,
+    rate_file: Option<PathBuf>,
+    ingestion_file: Option<PathBuf>,
+    faults_file: Option<PathBuf>,
+    error_bound: f32,
+    relative_error_bound: Option<f32>,
+    error_bounds_file: Option<PathBuf>,
+    nullable_fields: bool,
+    comparison: ComparisonFormat,
+    output_file: Option<PathBuf>,
+    record_dir: Option<PathBuf>,
+    replay_dir: Option<PathBuf>,
+    reset: bool,
+    verify: bool,
+}
+
+impl CliOptions {
+    /// Parse the options from the command line arguments `args`, excluding the program name.
+    /// Returns [`None`] if `--help` is given.
+    pub(super) fn try_from_args(args: Vec<String>) -> Result<Option<Self>, String> {
+        let mut options = Self {
+            nodes_file: PathBuf::from("data/nodes.json"),
+            data_file: PathBuf::from("data/wind_cleaned.parquet"),
+            mapping_file: None,
+            synthetic_file: None,
+            layout_file: None,
+            duration: Duration::from_secs(60),
+            passes: None,
+            count: 4000,
+            rate_file: None,
+            ingestion_file: None,
+            faults_file: None,
+            error_bound: 5.0,
+            relative_error_bound: None,
+            error_bounds_file: None,
+            nullable_fields: false,
+            comparison: ComparisonFormat::Parquet,
+            output_file: None,
+            record_dir: None,
+            replay_dir: None,
+            reset: false,
+            verify: false,
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("{arg} requires a value.\n\n{USAGE}"))
+            };
+
+            match arg.as_str() {
+                "--nodes" => options.nodes_file = value()?.into(),
+                "--data" => options.data_file = value()?.into(),
+                "--mapping" => options.mapping_file = Some(value()?.into()),
+                "--synthetic" => options.synthetic_file = Some(value()?.into()),
+                "--layout" => options.layout_file = Some(value()?.into()),
+                "--duration" => {
+                    options.duration = Duration::from_secs(parse_number(&arg, &value()?)?)
+                }
+                "--passes" => options.passes = Some(parse_number(&arg, &value()?)?),
+                "--count" => options.count = parse_number(&arg, &value()?)?,
+                "--rate" => options.rate_file = Some(value()?.into()),
+                "--ingestion" => options.ingestion_file = Some(value()?.into()),
+                "--faults" => options.faults_file = Some(value()?.into()),
+                "--error-bound" => options.error_bound = parse_number(&arg, &value()?)?,
+                "--relative-error-bound" => {
+                    options.relative_error_bound = Some(parse_number(&arg, &value()?)?)
+                }
+                "--error-bounds" => options.error_bounds_file = Some(value()?.into()),
+                "--nullable-fields" => options.nullable_fields = true,
+                "--comparison" => {
+                    options.comparison = match value()?.to_lowercase().as_str() {
+                        "parquet" => ComparisonFormat::Parquet,
+                        "orc" => ComparisonFormat::Orc,
+                        comparison => {
+                            return Err(format!(
+                                "Unknown comparison format {comparison}, expected parquet or orc."
+                            ))
+                        }
+                    };
+                }
+                "--output" => options.output_file = Some(value()?.into()),
+                "--record" => options.record_dir = Some(value()?.into()),
+                "--replay" => options.replay_dir = Some(value()?.into()),
+                "--reset" => options.reset = true,
+                "--verify" => options.verify = true,
+                "--help" | "-h" => {
+                    println!("{USAGE}");
+                    return Ok(None);
+                }
+                _ => return Err(format!("Unknown argument {arg}.\n\n{USAGE}")),
+            }
+        }
+
+        Ok(Some(options))
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(arg: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{arg} must be a non-negative number, got {value}."))
+}
+
+fn read_json_file<T: DeserializeOwned>(path: &PathBuf) -> Result<T, DemoError> {
+    let json = std::fs::read_to_string(path).map_err(|error| {
+        DemoError::Configuration(format!("Could not read {}: {error}", path.display()))
+    })?;
+
+    serde_json::from_str(&json).map_err(|error| {
+        DemoError::Configuration(format!("Could not parse {}: {error}", path.display()))
+    })
+}
+
+/// Totals of the metrics carried by the events, used for the summary at the end of a run.
+#[derive(Default)]
+struct Totals {
+    ingested_size: u64,
+    ingested_arrow_size: u64,
+    ingested_csv_size: u64,
+    modelardb_size: u64,
+    comparison_size: u64,
+}
+
+struct CliOutput {
+    writer: Box<dyn Write + Send>,
+    start: Instant,
+    totals: Totals,
+}
+
+#[derive(Serialize)]
+struct CliEvent<'a> {
+    elapsed_ms: u128,
+    event: &'a str,
+    payload: Value,
+}
+
+/// Writes each event as a line of JSON to standard output or a file.
+#[derive(Clone)]
+struct CliEmitter {
+    output: Arc<Mutex<CliOutput>>,
+    /// Notified when the ingestion of a finite run has completed.
+    ingestion_completed: Arc<Notify>,
+    session_recorder: Arc<SessionRecorder>,
+}
+
+impl CliEmitter {
+    fn try_new(output_file: Option<&PathBuf>) -> Result<Self, DemoError> {
+        let writer: Box<dyn Write + Send> = match output_file {
+            Some(path) => Box::new(BufWriter::new(File::create(path).map_err(|error| {
+                DemoError::Configuration(format!("Could not create {}: {error}", path.display()))
+            })?)),
+            None => Box::new(io::stdout()),
+        };
+
+        Ok(Self {
+            output: Arc::new(Mutex::new(CliOutput {
+                writer,
+                start: Instant::now(),
+                totals: Totals::default(),
+            })),
+            ingestion_completed: Arc::new(Notify::new()),
+            session_recorder: Arc::default(),
+        })
+    }
+
+    fn summary(&self) -> Summary {
+        let output = self.output.lock().unwrap();
+        let totals = &output.totals;
+
+        let compression_ratio = |size: u64| {
+            if size > 0 {
+                Some(totals.ingested_size as f64 / size as f64)
+            } else {
+                None
+            }
+        };
+
+        Summary {
+            duration_ms: output.start.elapsed().as_millis(),
+            ingested_size: totals.ingested_size,
+            ingested_arrow_size: totals.ingested_arrow_size,
+            ingested_csv_size: totals.ingested_csv_size,
+            modelardb_size: totals.modelardb_size,
+            comparison_size: totals.comparison_size,
+            modelardb_compression_ratio: compression_ratio(totals.modelardb_size),
+            comparison_compression_ratio: compression_ratio(totals.comparison_size),
+        }
+    }
+}
+
+impl EventEmitter for CliEmitter {
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        self.session_recorder.record_event(event, &payload);
+
+        let payload = serde_json::to_value(payload).unwrap();
+        let mut output = self.output.lock().unwrap();
+
+        // Keep track of the metrics so the compression ratios can be computed at the end.
+        match event {
+            "data-ingested" => {
+                output.totals.ingested_size += payload["size"].as_u64().unwrap_or(0);
+                output.totals.ingested_arrow_size += payload["arrow_size"].as_u64().unwrap_or(0);
+                output.totals.ingested_csv_size += payload["csv_size"].as_u64().unwrap_or(0);
+            }
+            "remote-object-store-size" => {
+                let table_size = payload["table_size"].as_u64().unwrap_or(0);
+                match payload["node_type"].as_str() {
+                    Some("modelardb") => output.totals.modelardb_size = table_size,
+                    Some("comparison") => output.totals.comparison_size = table_size,
+                    _ => (),
+                }
+            }
+            "ingestion-completed" => self.ingestion_completed.notify_one(),
+            _ => (),
+        }
+
+        let cli_event = CliEvent {
+            elapsed_ms: output.start.elapsed().as_millis(),
+            event,
+            payload,
+        };
+
+        let line = serde_json::to_string(&cli_event).unwrap();
+        writeln!(output.writer, "{line}").unwrap();
+        output.writer.flush().unwrap();
+    }
+
+    fn session_recorder(&self) -> Option<Arc<SessionRecorder>> {
+        Some(self.session_recorder.clone())
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct Summary {
+    duration_ms: u128,
+    /// The uncompressed Arrow IPC size of the ingested rows, which the compression ratios use.
+    ingested_size: u64,
+    ingested_arrow_size: u64,
+    ingested_csv_size: u64,
+    modelardb_size: u64,
+    comparison_size: u64,
+    modelardb_compression_ratio: Option<f64>,
+    comparison_compression_ratio: Option<f64>,
+}
+
+/// Run the create, ingest, flush, and measure pipeline of the demo for the duration given in
+/// `options`, or until the ingestion completes if the run is finite, and end with a `summary` event
+/// with the total sizes and compression ratios.
+pub(super) async fn run(options: CliOptions) -> Result<(), DemoError> {
+    let topology =
+        Topology::try_from_file(&options.nodes_file).map_err(DemoError::Configuration)?;
+    let storage_config = StorageConfig::try_from_env().map_err(DemoError::Configuration)?;
+
+    let modelardb_remote_object_store = storage_config
+        .build_object_store("modelardb")
+        .map_err(DemoError::Configuration)?;
+    let comparison_remote_object_store = storage_config
+        .build_object_store("comparison")
+        .map_err(DemoError::Configuration)?;
+
+    let emitter = CliEmitter::try_new(options.output_file.as_ref())?;
+
+    if let Some(path) = &options.record_dir {
+        emitter.session_recorder.start(path)?;
+    }
+
+    if options.reset {
+        crate::reset_nodes(&topology).await?;
+    }
+
+    // The recorded requests, including the creation of the table, are sent to the nodes instead of
+    // running the pipeline.
+    if let Some(path) = &options.replay_dir {
+        session::replay_session(
+            emitter.clone(),
+            path.clone(),
+            ReplaySpeed::Realtime,
+            modelardb_remote_object_store.clone(),
+            comparison_remote_object_store.clone(),
+        )
+        .await?;
+
+        return emit_summary(
+            &emitter,
+            modelardb_remote_object_store,
+            comparison_remote_object_store,
+        )
+        .await;
+    }
+
+    let data_source = match &options.synthetic_file {
+        Some(path) => DataSource::Synthetic(read_json_file(path)?),
+        None => {
+            let mapping = match &options.mapping_file {
+                Some(path) => read_json_file(path)?,
+                None => ColumnMapping::wind(),
+            };
+
+            DataSource::File(Dataset::try_new(options.data_file.clone(), &mapping)?)
+        }
+    };
+
+    let turbine_layout = match &options.layout_file {
+        Some(path) => read_json_file(path)?,
+        None => TurbineLayout::default(),
+    };
+
+    let error_bounds = match (&options.error_bounds_file, options.relative_error_bound) {
+        (Some(path), _) => read_json_file(path)?,
+        (None, Some(percent)) => ErrorBounds::uniform(ColumnErrorBound::Relative(percent)),
+        (None, None) => ErrorBounds::uniform(ColumnErrorBound::Absolute(options.error_bound)),
+    };
+
+    crate::create_time_series_table(
+        &emitter,
+        topology.manager_url.clone(),
+        &data_source,
+        options.nullable_fields,
+        &error_bounds,
+    )
+    .await?;
+
+    let rate = match &options.rate_file {
+        Some(path) => read_json_file(path)?,
+        None => IngestionRate::constant(options.count),
+    };
+
+    let mut ingestion_options = match &options.ingestion_file {
+        Some(path) => read_json_file(path)?,
+        None => crate::IngestionOptions::default(),
+    };
+
+    if options.passes.is_some() {
+        ingestion_options.passes = options.passes;
+    }
+
+    let ingestion_source = crate::IngestionSource {
+        data_source,
+        turbine_layout,
+        nullable_fields: options.nullable_fields,
+    };
+    crate::check_ingestion(&ingestion_source, &rate, &ingestion_options)?;
+
+    let timestamp_column = ingestion_source.table_schema().field(0).name().clone();
+    let written_rows = Arc::new(Mutex::new(WrittenRows::default()));
+
+    let fault_injector = Arc::new(FaultInjector::default());
+    if let Some(path) = &options.faults_file {
+        fault_injector.set_faults(read_json_file(path)?);
+    }
+
+    let topology_sender = watch::Sender::new(topology);
+    let health_sender = watch::Sender::new(HashMap::new());
+
+    let supervisor = Supervisor::new(emitter.clone());
+
+    supervisor.start(crate::FAULT_INJECTION_TASK, {
+        let emitter = emitter.clone();
+        let topology_receiver = topology_sender.subscribe();
+        let fault_injector = fault_injector.clone();
+        move || {
+            faults::inject_container_faults_task(
+                emitter.clone(),
+                topology_receiver.clone(),
+                fault_injector.clone(),
+            )
+            .map(Ok)
+        }
+    });
+
+    supervisor.start(crate::DISCOVERY_TASK, {
+        let emitter = emitter.clone();
+        let topology_sender = topology_sender.clone();
+        move || discovery::discover_nodes_task(emitter.clone(), topology_sender.clone()).map(Ok)
+    });
+
+    supervisor.start(crate::HEALTH_TASK, {
+        let emitter = emitter.clone();
+        let topology_receiver = topology_sender.subscribe();
+        let health_sender = health_sender.clone();
+        move || {
+            health::monitor_health_task(
+                emitter.clone(),
+                topology_receiver.clone(),
+                health_sender.clone(),
+            )
+            .map(Ok)
+        }
+    });
+
+    supervisor.start(crate::INGESTION_TASK, {
+        let emitter = emitter.clone();
+        let topology_receiver = topology_sender.subscribe();
+        let health_receiver = health_sender.subscribe();
+        let ingestion_offsets = crate::IngestionOffsets::default();
+        let rate_generator = Arc::new(Mutex::new(RateGenerator::new(rate)));
+        let write_options = WriteOptions {
+            comparison: options.comparison,
+            fault_injector: fault_injector.clone(),
+            written_rows: written_rows.clone(),
+            modelardb_remote_object_store: modelardb_remote_object_store.clone(),
+            comparison_remote_object_store: comparison_remote_object_store.clone(),
+        };
+        move || {
+            crate::ingest_into_table_task(
+                emitter.clone(),
+                topology_receiver.clone(),
+                health_receiver.clone(),
+                ingestion_source.clone(),
+                ingestion_options.clone(),
+                ingestion_offsets.clone(),
+                rate_generator.clone(),
+                write_options.clone(),
+            )
+        }
+    });
+
+    supervisor.start(crate::FLUSH_MODELARDB_TASK, {
+        let emitter = emitter.clone();
+        let topology_receiver = topology_sender.subscribe();
+        let health_receiver = health_sender.subscribe();
+        let object_store = modelardb_remote_object_store.clone();
+        let fault_injector = fault_injector.clone();
+        move || {
+            crate::flush_modelardb_nodes_task(
+                emitter.clone(),
+                topology_receiver.clone(),
+                health_receiver.clone(),
+                object_store.clone(),
+                fault_injector.clone(),
+            )
+            .map(Ok)
+        }
+    });
+
+    supervisor.start(crate::FLUSH_COMPARISON_TASK, {
+        let emitter = emitter.clone();
+        let topology_receiver = topology_sender.subscribe();
+        let health_receiver = health_sender.subscribe();
+        let object_store = comparison_remote_object_store.clone();
+        let fault_injector = fault_injector.clone();
+        move || {
+            crate::flush_comparison_nodes_task(
+                emitter.clone(),
+                topology_receiver.clone(),
+                health_receiver.clone(),
+                object_store.clone(),
+                fault_injector.clone(),
+            )
+            .map(Ok)
+        }
+    });
+
+    // A finite run ends when the ingestion has completed and the nodes have been flushed.
+    let _ = time::timeout(options.duration, emitter.ingestion_completed.notified()).await;
+
+    supervisor.pause_all(&[
+        crate::DISCOVERY_TASK,
+        crate::HEALTH_TASK,
+        crate::INGESTION_TASK,
+        crate::FLUSH_MODELARDB_TASK,
+        crate::FLUSH_COMPARISON_TASK,
+        crate::FAULT_INJECTION_TASK,
+    ]);
+
+    // Unpause the containers that are still paused by a fault so the cluster is left running.
+    fault_injector.set_faults(vec![]);
+    let topology = topology_sender.borrow().clone();
+    faults::pause_containers(&emitter, &topology, &fault_injector).await;
+
+    if options.verify {
+        let cloud_url = topology.cloud_urls.first().ok_or_else(|| {
+            DemoError::Configuration("There is no cloud node to verify the rows with.".to_owned())
+        })?;
+
+        let written_rows = written_rows.lock().unwrap().clone();
+        let verification_report = verification::try_verify(
+            written_rows,
+            crate::TABLE_NAME,
+            &timestamp_column,
+            cloud_url,
+            comparison_remote_object_store.clone(),
+        )
+        .await?;
+
+        emitter.emit_event("verification-report", verification_report);
+    }
+
+    emit_summary(
+        &emitter,
+        modelardb_remote_object_store,
+        comparison_remote_object_store,
+    )
+    .await
+}
+
+/// Measure the final size of the tables, so the summary includes the last flushed data, emit the
+/// `summary` event, and stop the recording of the session, if any.
+async fn emit_summary(
+    emitter: &CliEmitter,
+    modelardb_remote_object_store: Arc<dyn ObjectStore>,
+    comparison_remote_object_store: Arc<dyn ObjectStore>,
+) -> Result<(), DemoError> {
+    crate::emit_remote_object_store_table_size(
+        emitter.clone(),
+        modelardb_remote_object_store,
+        "modelardb".to_owned(),
+    )
+    .await?;
+
+    crate::emit_remote_object_store_table_size(
+        emitter.clone(),
+        comparison_remote_object_store,
+        "comparison".to_owned(),
+    )
+    .await?;
+
+    emitter.emit_event("summary", emitter.summary());
+
+    emitter.session_recorder.stop()
+}
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use arrow_flight::flight_service_client::FlightServiceClient;
+use arrow_flight::Empty;
+use modelardb_embedded::operations::client::{Client, Node};
+use serde::Serialize;
+use tokio::sync::watch;
+use tokio::time;
+use tonic::transport::Channel;
+
+use crate::events::EventEmitter;
+use crate::topology::Topology;
+
+/// How often each node is probed.
+const PROBE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long a probe or a connection attempt may take before it is considered failed.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Probes that take longer than this mark the node as degraded.
+const DEGRADED_LATENCY: Duration = Duration::from_millis(500);
+
+/// Number of consecutive failed probes before a node is considered down.
+const DOWN_AFTER_FAILURES: u32 = 3;
+
+/// The delay before the first reconnection attempt, doubled for each failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The maximum delay between reconnection attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// The health of a node as determined by the most recent probes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum NodeHealth {
+    Up,
+    Degraded,
+    Down,
+}
+
+/// The health of each node, indexed by the node's URL. Nodes that have not been probed yet are not
+/// included and are assumed to be up.
+pub(super) type HealthMap = HashMap<String, NodeHealth>;
+
+/// Return [`true`] if the node with `url` has not been determined to be down.
+pub(super) fn is_available(node_health: &HealthMap, url: &str) -> bool {
+    node_health.get(url) != Some(&NodeHealth::Down)
+}
+
+#[derive(Clone, Serialize)]
+struct NodeHealthChanged {
+    url: String,
+    health: NodeHealth,
+}
+
+/// Periodically probe the edge and cloud nodes in the topology, update the health of the nodes in
+/// `health_sender`, and emit a `node-health` event each time the health of a node changes.
+pub(super) async fn monitor_health_task<E: EventEmitter>(
+    emitter: E,
+    topology_receiver: watch::Receiver<Topology>,
+    health_sender: watch::Sender<HealthMap>,
+) {
+    let mut consecutive_failures: HashMap<String, u32> = HashMap::new();
+
+    loop {
+        let urls = {
+            let topology = topology_receiver.borrow();
+
+            let mut urls: Vec<String> = topology
+                .edge_nodes
+                .iter()
+                .flat_map(|(modelardb_node, comparison_node)| {
+                    [
+                        modelardb_node.url().to_owned(),
+                        comparison_node.url().to_owned(),
+                    ]
+                })
+                .collect();
+
+            urls.extend(topology.cloud_urls.iter().cloned());
+            urls
+        };
+
+        forget_removed_nodes(&mut consecutive_failures, &urls);
+
+        let mut node_health = HashMap::new();
+        for url in urls {
+            let failures = consecutive_failures.entry(url.clone()).or_insert(0);
+
+            let health = update_health(probe(&url).await, failures);
+
+            if health_sender.borrow().get(&url) != Some(&health) {
+                emitter.emit_event(
+                    "node-health",
+                    NodeHealthChanged {
+                        url: url.clone(),
+                        health,
+                    },
+                );
+            }
+
+            node_health.insert(url, health);
+        }
+
+        health_sender.send_if_modified(|current_node_health| {
+            let changed = *current_node_health != node_health;
+            *current_node_health = node_health;
+            changed
+        });
+
+        time::sleep(PROBE_INTERVAL).await;
+    }
+}
+
+/// Forget the failures of the nodes that are not in `urls`, so a node that is removed and added
+/// again later starts out healthy.
+fn forget_removed_nodes(consecutive_failures: &mut HashMap<String, u32>, urls: &[String]) {
+    consecutive_failures.retain(|url, _failures| urls.contains(url));
+}
+
+/// Return the health of a node given the `latency` of its most recent probe, or [`None`] if the
+/// probe failed, and update the node's number of consecutive `failures` accordingly.
+fn update_health(latency: Option<Duration>, failures: &mut u32) -> NodeHealth {
+    match latency {
+        Some(latency) => {
+            *failures = 0;
+
+            if latency > DEGRADED_LATENCY {
+                NodeHealth::Degraded
+            } else {
+                NodeHealth::Up
+            }
+        }
+        None => {
+            *failures += 1;
+
+            if *failures >= DOWN_AFTER_FAILURES {
+                NodeHealth::Down
+            } else {
+                NodeHealth::Degraded
+            }
+        }
+    }
+}
+
+/// Probe the node with `url` by listing its actions. Returns how long the probe took if it
+/// succeeded within [`PROBE_TIMEOUT`].
+async fn probe(url: &str) -> Option<Duration> {
+    let start = Instant::now();
+
+    let result = time::timeout(PROBE_TIMEOUT, async {
+        let mut flight_client = FlightServiceClient::connect(url.to_owned()).await.ok()?;
+        flight_client.list_actions(Empty {}).await.ok()
+    })
+    .await;
+
+    match result {
+        Ok(Some(_response)) => Some(start.elapsed()),
+        _ => None,
+    }
+}
+
+/// A connection to a pair of ModelarDB and comparison edge nodes. The connection is dropped when
+/// either node is down and reestablished with exponential backoff once both are available.
+pub(super) struct EdgeConnection {
+    modelardb_node: Node,
+    comparison_node: Node,
+    clients: Option<(Client, FlightServiceClient<Channel>)>,
+    next_attempt: Instant,
+    backoff: Duration,
+}
+
+impl EdgeConnection {
+    pub(super) fn new(modelardb_node: Node, comparison_node: Node) -> Self {
+        Self {
+            modelardb_node,
+            comparison_node,
+            clients: None,
+            next_attempt: Instant::now(),
+            backoff: INITIAL_BACKOFF,
+        }
+    }
+
+    /// Return clients for the pair of nodes if both are available, connecting to them first if
+    /// necessary. Returns [`None`] if a node is down or the connection could not be established.
+    pub(super) async fn clients(
+        &mut self,
+        node_health: &HealthMap,
+    ) -> Option<(Client, FlightServiceClient<Channel>)> {
+        if !is_available(node_health, self.modelardb_node.url())
+            || !is_available(node_health, self.comparison_node.url())
+        {
+            self.clients = None;
+            return None;
+        }
+
+        if self.clients.is_none() && Instant::now() >= self.next_attempt {
+            match self.connect().await {
+                Some(clients) => {
+                    self.clients = Some(clients);
+                    self.backoff = INITIAL_BACKOFF;
+                }
+                None => {
+                    self.next_attempt = Instant::now() + self.backoff;
+                    self.backoff = next_backoff(self.backoff);
+                }
+            }
+        }
+
+        self.clients.clone()
+    }
+
+    async fn connect(&self) -> Option<(Client, FlightServiceClient<Channel>)> {
+        let modelardb_client =
+            time::timeout(PROBE_TIMEOUT, Client::connect(self.modelardb_node.clone()))
+                .await
+                .ok()?
+                .ok()?;
+
+        let comparison_client = time::timeout(
+            PROBE_TIMEOUT,
+            FlightServiceClient::connect(self.comparison_node.url().to_owned()),
+        )
+        .await
+        .ok()?
+        .ok()?;
+
+        Some((modelardb_client, comparison_client))
+    }
+}
+
+/// Return the delay before the next reconnection attempt after an attempt delayed by `backoff`
+/// failed.
+fn next_backoff(backoff: Duration) -> Duration {
+    (backoff * 2).min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_health_is_up_for_fast_probes() {
+        let mut failures = 2;
+
+        assert_eq!(
+            update_health(Some(DEGRADED_LATENCY), &mut failures),
+            NodeHealth::Up
+        );
+        assert_eq!(failures, 0);
+    }
+
+    #[test]
+    fn test_update_health_is_degraded_for_slow_probes() {
+        let mut failures = 2;
+
+        assert_eq!(
+            update_health(
+                Some(DEGRADED_LATENCY + Duration::from_millis(1)),
+                &mut failures
+            ),
+            NodeHealth::Degraded
+        );
+        assert_eq!(failures, 0);
+    }
+
+    #[test]
+    fn test_update_health_is_down_after_consecutive_failures() {
+        let mut failures = 0;
+
+        for _ in 1..DOWN_AFTER_FAILURES {
+            assert_eq!(update_health(None, &mut failures), NodeHealth::Degraded);
+        }
+
+        assert_eq!(update_health(None, &mut failures), NodeHealth::Down);
+        assert_eq!(update_health(None, &mut failures), NodeHealth::Down);
+        assert_eq!(failures, DOWN_AFTER_FAILURES + 1);
+    }
+
+    #[test]
+    fn test_update_health_is_up_after_recovering() {
+        let mut failures = 0;
+        for _ in 0..DOWN_AFTER_FAILURES {
+            update_health(None, &mut failures);
+        }
+
+        assert_eq!(
+            update_health(Some(Duration::ZERO), &mut failures),
+            NodeHealth::Up
+        );
+        assert_eq!(update_health(None, &mut failures), NodeHealth::Degraded);
+    }
+
+    #[test]
+    fn test_next_backoff_doubles_until_max_backoff() {
+        let mut backoff = INITIAL_BACKOFF;
+        let mut backoffs = vec![];
+        for _ in 0..7 {
+            backoffs.push(backoff.as_secs());
+            backoff = next_backoff(backoff);
+        }
+
+        assert_eq!(backoffs, vec![1, 2, 4, 8, 16, 30, 30]);
+    }
+
+    #[test]
+    fn test_forget_removed_nodes() {
+        let mut consecutive_failures = HashMap::from([
+            ("grpc://127.0.0.1:9999".to_owned(), 3),
+            ("grpc://127.0.0.1:9998".to_owned(), 1),
+        ]);
+
+        forget_removed_nodes(
+            &mut consecutive_failures,
+            &["grpc://127.0.0.1:9998".to_owned()],
+        );
+
+        assert_eq!(
+            consecutive_failures,
+            HashMap::from([("grpc://127.0.0.1:9998".to_owned(), 1)])
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt::{self, Write};
+
+use arrow::array::{Array, RecordBatch};
+use arrow::ipc::writer::{DictionaryTracker, EncodedData, IpcDataGenerator, IpcWriteOptions};
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use serde::Serialize;
+
+use crate::error::DemoError;
+
+/// The size of the rows ingested into an edge node, measured from the data that is written to it.
+#[derive(Clone, Serialize)]
+pub(super) struct IngestedSize {
+    table_name: String,
+    /// URL of the ModelarDB edge node the rows were ingested into.
+    url: String,
+    /// The uncompressed Arrow IPC size, which is the baseline the compression ratios use.
+    size: usize,
+    arrow_size: usize,
+    ipc_size: usize,
+    csv_size: usize,
+    columns: Vec<ColumnSize>,
+}
+
+/// The size of a column of the ingested rows.
+#[derive(Clone, Serialize)]
+struct ColumnSize {
+    name: String,
+    /// The memory used by the Arrow buffers of the column.
+    arrow_size: usize,
+    /// The size of the column in the body of an uncompressed Arrow IPC message.
+    ipc_size: usize,
+    /// The size of the column when its values are written as text with a delimiter after each.
+    csv_size: usize,
+}
+
+impl IngestedSize {
+    /// Measure the size of `record_batch` when it is ingested into the table with `table_name` on
+    /// the edge node with `url`.
+    pub(super) fn try_new(
+        table_name: &str,
+        url: &str,
+        record_batch: &RecordBatch,
+    ) -> Result<Self, DemoError> {
+        let schema = record_batch.schema();
+
+        let columns = schema
+            .fields()
+            .iter()
+            .zip(record_batch.columns())
+            .enumerate()
+            .map(|(index, (field, column))| {
+                Ok(ColumnSize {
+                    name: field.name().clone(),
+                    arrow_size: column.to_data().get_slice_memory_size()?,
+                    ipc_size: ipc_body_size(&record_batch.project(&[index])?)?,
+                    csv_size: csv_size(column.as_ref())?,
+                })
+            })
+            .collect::<Result<Vec<_>, DemoError>>()?;
+
+        // The IPC size of the whole batch also includes the metadata of the message.
+        let ipc_size = ipc_message_size(record_batch)?;
+
+        Ok(Self {
+            table_name: table_name.to_owned(),
+            url: url.to_owned(),
+            size: ipc_size,
+            arrow_size: columns.iter().map(|column| column.arrow_size).sum(),
+            ipc_size,
+            csv_size: columns.iter().map(|column| column.csv_size).sum(),
+            columns,
+        })
+    }
+}
+
+/// Return the size of the metadata and body of the IPC message with `record_batch`.
+fn ipc_message_size(record_batch: &RecordBatch) -> Result<usize, DemoError> {
+    let (_dictionaries, encoded_data) = encode_ipc(record_batch)?;
+    Ok(encoded_data.ipc_message.len() + encoded_data.arrow_data.len())
+}
+
+/// Return the size of the body of the IPC message with `record_batch`.
+fn ipc_body_size(record_batch: &RecordBatch) -> Result<usize, DemoError> {
+    let (_dictionaries, encoded_data) = encode_ipc(record_batch)?;
+    Ok(encoded_data.arrow_data.len())
+}
+
+/// Encode `record_batch` as an IPC message and the dictionaries it uses.
+fn encode_ipc(record_batch: &RecordBatch) -> Result<(Vec<EncodedData>, EncodedData), DemoError> {
+    let mut dictionary_tracker = DictionaryTracker::new(false);
+
+    Ok(IpcDataGenerator::default().encoded_batch(
+        record_batch,
+        &mut dictionary_tracker,
+        &IpcWriteOptions::default(),
+    )?)
+}
+
+/// Return the number of bytes `column` takes up when each value is written as text followed by a
+/// delimiter, as in a CSV file without quoting. Null values are written as empty strings.
+fn csv_size(column: &dyn Array) -> Result<usize, DemoError> {
+    let options = FormatOptions::default();
+    let formatter = ArrayFormatter::try_new(column, &options)?;
+
+    let mut counter = ByteCounter(0);
+    for index in 0..column.len() {
+        write!(counter, "{}", formatter.value(index)).unwrap();
+    }
+
+    Ok(counter.0 + column.len())
+}
+
+/// Counts the bytes written to it instead of storing them.
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write_str(&mut self, string: &str) -> fmt::Result {
+        self.0 += string.len();
+        Ok(())
+    }
+}
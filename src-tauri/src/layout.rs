@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+
+/// The number of edge nodes in the first park when the turbines are spread between the edge nodes.
+const EDGE_NODES_IN_FIRST_PARK: usize = 5;
+
+/// Which parks the edge nodes are gateways for and which turbines in them each edge node ingests
+/// data for. If no parks are given, the turbines are spread evenly between the edge nodes.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(super) struct TurbineLayout {
+    #[serde(default)]
+    pub(super) parks: Vec<Park>,
+}
+
+/// A wind park and the edge nodes that ingest data for its turbines.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(super) struct Park {
+    pub(super) park_id: String,
+    pub(super) edge_nodes: Vec<ParkEdgeNode>,
+}
+
+/// The turbines in a park that an edge node ingests data for.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(super) struct ParkEdgeNode {
+    /// Position of the edge node in the topology.
+    pub(super) edge_node: usize,
+    pub(super) turbines: Turbines,
+}
+
+/// The turbines either as a number of turbines to generate names for or as their names.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub(super) enum Turbines {
+    Count(usize),
+    Ids(Vec<String>),
+}
+
+/// The values of the tags that identify a turbine in the table.
+#[derive(Clone, Debug)]
+pub(super) struct TurbineId {
+    pub(super) park_id: String,
+    pub(super) windmill_id: String,
+}
+
+impl TurbineLayout {
+    /// Return the turbines each of `num_nodes` edge nodes ingests data for. If the layout has no
+    /// parks, `default_turbines` turbines are spread evenly between the edge nodes instead.
+    pub(super) fn turbines_per_node(
+        &self,
+        num_nodes: usize,
+        default_turbines: usize,
+    ) -> Vec<Vec<TurbineId>> {
+        let mut turbines_per_node = vec![vec![]; num_nodes];
+
+        if self.parks.is_empty() {
+            for turbine_index in 0..default_turbines {
+                let node_index = turbine_index % num_nodes.max(1);
+                let park_number = if node_index < EDGE_NODES_IN_FIRST_PARK {
+                    1
+                } else {
+                    2
+                };
+
+                if let Some(turbines) = turbines_per_node.get_mut(node_index) {
+                    turbines.push(TurbineId {
+                        park_id: format!("park_{park_number}"),
+                        windmill_id: format!("windmill_{}", turbine_index + 1),
+                    });
+                }
+            }
+
+            return turbines_per_node;
+        }
+
+        // Turbines given as a count are numbered across the layout, so their names are unique.
+        let mut next_turbine_number = 1;
+
+        for park in &self.parks {
+            for park_edge_node in &park.edge_nodes {
+                let windmill_ids = match &park_edge_node.turbines {
+                    Turbines::Count(count) => {
+                        let first_turbine_number = next_turbine_number;
+                        next_turbine_number += count;
+
+                        (first_turbine_number..next_turbine_number)
+                            .map(|turbine_number| format!("windmill_{turbine_number}"))
+                            .collect()
+                    }
+                    Turbines::Ids(windmill_ids) => windmill_ids.clone(),
+                };
+
+                // Edge nodes that are not in the topology do not ingest any data.
+                if let Some(turbines) = turbines_per_node.get_mut(park_edge_node.edge_node) {
+                    turbines.extend(windmill_ids.into_iter().map(|windmill_id| TurbineId {
+                        park_id: park.park_id.clone(),
+                        windmill_id,
+                    }));
+                }
+            }
+        }
+
+        turbines_per_node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn windmill_ids(turbines: &[TurbineId]) -> Vec<&str> {
+        turbines
+            .iter()
+            .map(|turbine| turbine.windmill_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_turbines_per_node_spreads_uneven_split() {
+        let turbines_per_node = TurbineLayout::default().turbines_per_node(3, 7);
+
+        assert_eq!(
+            windmill_ids(&turbines_per_node[0]),
+            ["windmill_1", "windmill_4", "windmill_7"]
+        );
+        assert_eq!(
+            windmill_ids(&turbines_per_node[1]),
+            ["windmill_2", "windmill_5"]
+        );
+        assert_eq!(
+            windmill_ids(&turbines_per_node[2]),
+            ["windmill_3", "windmill_6"]
+        );
+    }
+
+    #[test]
+    fn test_turbines_per_node_with_fewer_turbines_than_nodes() {
+        let turbines_per_node = TurbineLayout::default().turbines_per_node(7, 6);
+
+        assert_eq!(turbines_per_node.len(), 7);
+        assert!(turbines_per_node[6].is_empty());
+        assert_eq!(turbines_per_node[4][0].park_id, "park_1");
+        assert_eq!(turbines_per_node[5][0].park_id, "park_2");
+    }
+
+    #[test]
+    fn test_turbines_per_node_without_nodes() {
+        assert!(TurbineLayout::default().turbines_per_node(0, 4).is_empty());
+    }
+
+    #[test]
+    fn test_turbines_per_node_numbers_counts_across_parks() {
+        let layout = TurbineLayout {
+            parks: vec![
+                Park {
+                    park_id: "north".to_owned(),
+                    edge_nodes: vec![
+                        ParkEdgeNode {
+                            edge_node: 0,
+                            turbines: Turbines::Count(2),
+                        },
+                        ParkEdgeNode {
+                            edge_node: 1,
+                            turbines: Turbines::Count(1),
+                        },
+                    ],
+                },
+                Park {
+                    park_id: "south".to_owned(),
+                    edge_nodes: vec![
+                        ParkEdgeNode {
+                            edge_node: 0,
+                            turbines: Turbines::Ids(vec!["t1".to_owned()]),
+                        },
+                        ParkEdgeNode {
+                            edge_node: 5,
+                            turbines: Turbines::Count(3),
+                        },
+                    ],
+                },
+            ],
+        };
+
+        let turbines_per_node = layout.turbines_per_node(2, 0);
+
+        assert_eq!(
+            windmill_ids(&turbines_per_node[0]),
+            ["windmill_1", "windmill_2", "t1"]
+        );
+        assert_eq!(turbines_per_node[0][2].park_id, "south");
+        assert_eq!(windmill_ids(&turbines_per_node[1]), ["windmill_3"]);
+    }
+}
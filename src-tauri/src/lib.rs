@@ -1,141 +1,398 @@
 use std::collections::HashMap;
 use std::iter;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::Duration;
 
-use arrow::array::{RecordBatch, StringArray};
-use arrow::compute;
+use arrow::array::{ArrayRef, RecordBatch, StringArray};
+use arrow::datatypes::SchemaRef;
 use arrow_flight::flight_service_client::FlightServiceClient;
 use arrow_flight::{Action, Ticket};
 use arrow_json::ArrayWriter;
 use bollard::Docker;
-use datafusion::parquet::arrow::ParquetRecordBatchStreamBuilder;
 use datafusion::physical_plan::common;
-use futures_util::TryStreamExt;
+use futures_util::FutureExt;
 use modelardb_embedded::operations::client::{Client, Node};
 use modelardb_embedded::operations::Operations;
 use modelardb_embedded::TableType;
-use modelardb_types::types::{ErrorBound, TimestampBuilder};
-use object_store::aws::AmazonS3;
+use modelardb_types::types::TimestampArray;
+use object_store::ObjectStore;
 use serde::{Deserialize, Serialize};
 use tauri::path::BaseDirectory;
-use tauri::{AppHandle, Emitter, Manager, State};
-use tokio::sync::Mutex;
-use tokio::task::JoinHandle;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::{watch, Mutex};
 use tokio::time;
-use tonic::transport::Channel;
 
+use crate::dataset::{ColumnMapping, ColumnMappingOverrides, DataSource, Dataset, PartitionReader};
+use crate::disorder::{Disorder, DisorderGenerator};
+use crate::dropout::{Dropout, DropoutGenerator};
+use crate::error::DemoError;
+use crate::error_bounds::{self, ErrorBounds};
+use crate::events::{self, EventEmitter, FailedEmits};
+use crate::faults::{AffectedRequest, Fault, FaultInjector, Operation};
+use crate::generator::{GeneratorConfig, TurbineGenerator};
+use crate::health::{EdgeConnection, HealthMap};
+use crate::ingested_size::IngestedSize;
+use crate::layout::{TurbineId, TurbineLayout};
+use crate::rate::{IngestionRate, RateGenerator, INGESTION_INTERVAL};
+use crate::replay::{Replay, ReplayClock, ReplaySpeed};
+use crate::session::{SessionEntry, SessionRecorder, System};
+use crate::storage::StorageConfig;
+use crate::supervisor::{RestartPolicy, Supervisor, TaskInfo};
+use crate::topology::{ClusterTopology, NodeConfig, Topology};
+use crate::verification::{VerificationReport, WrittenRows};
+use crate::writer::{Backpressure, ComparisonFormat, NodeWriter, WriteOptions, WriteRequest};
+
+mod cluster;
+mod dataset;
+mod discovery;
+mod disorder;
+mod dropout;
+mod error;
+mod error_bounds;
+mod events;
+mod faults;
+mod generator;
+mod headless;
+mod health;
+mod ingested_size;
+mod layout;
+mod rate;
+mod replay;
+mod session;
+mod storage;
+mod supervisor;
+mod topology;
 mod util;
+mod verification;
+mod writer;
 
 const TABLE_NAME: &str = "wind";
 
-const NODE_COUNT: u64 = 4;
+/// Environment variable that can be set to the path of a nodes file to use instead of the bundled one.
+const NODES_FILE_ENV: &str = "MODELARDB_DEMO_NODES_FILE";
+
+const INGESTION_TASK: &str = "ingestion";
+const FLUSH_MODELARDB_TASK: &str = "flush-modelardb";
+const FLUSH_COMPARISON_TASK: &str = "flush-comparison";
+const MONITOR_NODES_TASK: &str = "monitor-nodes";
+const DISCOVERY_TASK: &str = "discovery";
+const HEALTH_TASK: &str = "health";
+const FAULT_INJECTION_TASK: &str = "fault-injection";
+const REPLAY_SESSION_TASK: &str = "replay-session";
+
+/// The tasks that ingest data into, flush, and monitor the nodes. They are stopped when the demo is
+/// reset or the cluster is stopped, while discovery and health monitoring keep running.
+const PIPELINE_TASKS: [&str; 4] = [
+    INGESTION_TASK,
+    FLUSH_MODELARDB_TASK,
+    FLUSH_COMPARISON_TASK,
+    MONITOR_NODES_TASK,
+];
+
+/// The number of rows of the partition of the data set that each edge node has read, including the
+/// rows read in earlier passes.
+type IngestionOffsets = Arc<std::sync::Mutex<Vec<usize>>>;
 
 struct AppState {
-    ingestion_task: Option<JoinHandle<()>>,
-    flush_modelardb_task: Option<JoinHandle<()>>,
-    flush_comparison_task: Option<JoinHandle<()>>,
-    monitor_nodes_task: Option<JoinHandle<()>>,
-    modelardb_remote_object_store: AmazonS3,
-    comparison_remote_object_store: AmazonS3,
+    supervisor: Supervisor<AppHandle>,
+    /// The offsets into the edge nodes' partitions of the data set the ingestion continues from
+    /// when it is resumed.
+    ingestion_offsets: IngestionOffsets,
+    /// Where the rows ingested into the edge nodes come from.
+    data_source: DataSource,
+    /// Which turbines the rows ingested into each edge node are from.
+    turbine_layout: TurbineLayout,
+    /// If the table was created with nullable field columns, so values can be dropped out.
+    nullable_fields: bool,
+    modelardb_remote_object_store: Arc<dyn ObjectStore>,
+    comparison_remote_object_store: Arc<dyn ObjectStore>,
+    fault_injector: Arc<FaultInjector>,
+    /// The rows written to the edge nodes since the ingestion was started.
+    written_rows: Arc<std::sync::Mutex<WrittenRows>>,
+    topology: watch::Sender<Topology>,
+    node_health: watch::Sender<HealthMap>,
 }
 
 impl AppState {
-    fn new() -> Self {
-        let modelardb_remote_object_store = util::build_s3_object_store("modelardb".to_owned());
-        let comparison_remote_object_store = util::build_s3_object_store("comparison".to_owned());
-
-        Self {
-            ingestion_task: None,
-            flush_modelardb_task: None,
-            flush_comparison_task: None,
-            monitor_nodes_task: None,
+    fn try_new(
+        app: AppHandle,
+        topology: Topology,
+        data_source: DataSource,
+        storage_config: &StorageConfig,
+    ) -> Result<Self, String> {
+        let modelardb_remote_object_store = storage_config.build_object_store("modelardb")?;
+        let comparison_remote_object_store = storage_config.build_object_store("comparison")?;
+
+        Ok(Self {
+            supervisor: Supervisor::new(app),
+            ingestion_offsets: IngestionOffsets::default(),
+            data_source,
+            turbine_layout: TurbineLayout::default(),
+            nullable_fields: false,
             modelardb_remote_object_store,
             comparison_remote_object_store,
-        }
+            fault_injector: Arc::default(),
+            written_rows: Arc::default(),
+            topology: watch::Sender::new(topology),
+            node_health: watch::Sender::new(HashMap::new()),
+        })
+    }
+
+    /// Stop any running ingestion, flushing, monitoring, and replay tasks.
+    fn abort_tasks(&self) {
+        self.supervisor.pause_all(&PIPELINE_TASKS);
+
+        // The replay is not resumed with the pipeline, as it would start over from the beginning.
+        self.supervisor.pause_all(&[REPLAY_SESSION_TASK]);
     }
 }
 
 #[tauri::command]
-async fn reset_state(state: State<'_, Mutex<AppState>>) -> Result<(), String> {
+async fn start_cluster(app: AppHandle, state: State<'_, Mutex<AppState>>) -> Result<(), DemoError> {
+    let topology = state.lock().await.topology.borrow().clone();
+    cluster::start_cluster(&app, &topology).await
+}
+
+#[tauri::command]
+async fn stop_cluster(app: AppHandle, state: State<'_, Mutex<AppState>>) -> Result<(), DemoError> {
+    let topology = {
+        let state = state.lock().await;
+
+        // The tasks cannot reach the nodes once the containers are removed.
+        state.abort_tasks();
+        state.topology.borrow().clone()
+    };
+
+    cluster::stop_cluster(&app, &topology).await
+}
+
+#[tauri::command]
+async fn restart_node(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    url: String,
+) -> Result<(), DemoError> {
+    let topology = state.lock().await.topology.borrow().clone();
+    cluster::restart_node(&app, &topology, &url).await
+}
+
+#[tauri::command]
+async fn pause_demo(state: State<'_, Mutex<AppState>>) -> Result<(), DemoError> {
+    state.lock().await.abort_tasks();
+    Ok(())
+}
+
+#[tauri::command]
+async fn resume_demo(state: State<'_, Mutex<AppState>>) -> Result<(), DemoError> {
+    state.lock().await.supervisor.resume_all(&PIPELINE_TASKS);
+    Ok(())
+}
+
+#[tauri::command]
+async fn reset_state(state: State<'_, Mutex<AppState>>) -> Result<(), DemoError> {
     let state = state.lock().await;
 
     // Abort any running tasks.
-    if let Some(handle) = &state.ingestion_task {
-        handle.abort();
-    }
+    state.abort_tasks();
 
-    if let Some(handle) = &state.flush_modelardb_task {
-        handle.abort();
-    }
-
-    if let Some(handle) = &state.flush_comparison_task {
-        handle.abort();
-    }
+    let topology = state.topology.borrow().clone();
+    reset_nodes(&topology).await
+}
 
-    if let Some(handle) = &state.monitor_nodes_task {
-        handle.abort();
-    }
+/// Drop the table in the ModelarDB cluster and delete all files in the comparison edge nodes.
+async fn reset_nodes(topology: &Topology) -> Result<(), DemoError> {
+    let modelardb_manager_node = Node::Manager(topology.manager_url.clone());
+    let mut modelardb_client = connect_to_modelardb(modelardb_manager_node).await?;
 
-    // Drop the tables and delete all files.
-    let modelardb_manager_node = Node::Manager("grpc://127.0.0.1:9980".to_owned());
-    let mut modelardb_client = Client::connect(modelardb_manager_node).await.unwrap();
-    modelardb_client.drop(TABLE_NAME).await.unwrap();
+    modelardb_client
+        .drop(TABLE_NAME)
+        .await
+        .map_err(|error| DemoError::FlightStatus(error.to_string()))?;
 
-    for (_modelardb_node, comparison_node) in util::edge_nodes() {
-        let mut comparison_client = FlightServiceClient::connect(comparison_node.url().to_owned())
-            .await
-            .unwrap();
+    for (_modelardb_node, comparison_node) in &topology.edge_nodes {
+        let mut comparison_client =
+            FlightServiceClient::connect(comparison_node.url().to_owned()).await?;
 
         let action = Action {
             r#type: "ResetNode".to_owned(),
             body: vec![].into(),
         };
 
-        comparison_client.do_action(action).await.unwrap();
+        comparison_client.do_action(action).await?;
     }
 
     Ok(())
 }
 
+/// Connect to the ModelarDB `node`.
+async fn connect_to_modelardb(node: Node) -> Result<Client, DemoError> {
+    Client::connect(node)
+        .await
+        .map_err(|error| DemoError::Connection(error.to_string()))
+}
+
 #[tauri::command]
-async fn create_table(error_bound: usize) {
-    let modelardb_manager_node = Node::Manager("grpc://127.0.0.1:9980".to_owned());
-    let mut modelardb_client = Client::connect(modelardb_manager_node).await.unwrap();
+async fn set_dataset(
+    state: State<'_, Mutex<AppState>>,
+    path: String,
+    mapping: ColumnMapping,
+) -> Result<(), DemoError> {
+    // The data set is used the next time the table is created and the ingestion is started.
+    let dataset = Dataset::try_new(path.into(), &mapping)?;
+    state.lock().await.data_source = DataSource::File(dataset);
 
-    let table_schema = util::table_schema();
+    Ok(())
+}
 
-    let field_column_names = vec![
-        "wind_speed",
-        "pitch_angle",
-        "rotor_speed",
-        "active_power",
-        "cos_nacelle_dir",
-        "sin_nacelle_dir",
-        "cos_wind_dir",
-        "sin_wind_dir",
-        "cor_nacelle_direction",
-        "cor_wind_direction",
-    ];
+#[tauri::command]
+async fn set_synthetic_dataset(
+    state: State<'_, Mutex<AppState>>,
+    config: GeneratorConfig,
+) -> Result<(), DemoError> {
+    // The synthetic turbines are used the next time the table is created and the ingestion is
+    // started.
+    state.lock().await.data_source = DataSource::Synthetic(config);
 
-    let error_bounds: HashMap<String, ErrorBound> = field_column_names
-        .clone()
-        .into_iter()
-        .map(|name| {
-            (
-                name.to_owned(),
-                ErrorBound::try_new_absolute(error_bound as f32).unwrap(),
-            )
-        })
-        .collect();
+    Ok(())
+}
+
+/// Set which parks the edge nodes are gateways for and which of their turbines each edge node
+/// ingests data for. It is used the next time the ingestion is started.
+#[tauri::command]
+async fn set_turbine_layout(
+    state: State<'_, Mutex<AppState>>,
+    layout: TurbineLayout,
+) -> Result<(), DemoError> {
+    state.lock().await.turbine_layout = layout;
+
+    Ok(())
+}
 
-    let error_bound_table_type =
-        TableType::TimeSeriesTable(table_schema.clone(), error_bounds, HashMap::new());
+/// Create the time series table for the selected data source with `error_bounds` for its field
+/// columns. If `nullable_fields` is set, the field columns are nullable so values can be dropped
+/// out during the ingestion.
+#[tauri::command]
+async fn create_table(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    error_bounds: ErrorBounds,
+    nullable_fields: Option<bool>,
+) -> Result<(), DemoError> {
+    let nullable_fields = nullable_fields.unwrap_or(false);
+    let (manager_url, data_source) = {
+        let state = state.lock().await;
+        let manager_url = state.topology.borrow().manager_url.clone();
+        (manager_url, state.data_source.clone())
+    };
+
+    create_time_series_table(
+        &app,
+        manager_url,
+        &data_source,
+        nullable_fields,
+        &error_bounds,
+    )
+    .await?;
+
+    state.lock().await.nullable_fields = nullable_fields;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn propose_column_mapping(path: String) -> Result<ColumnMapping, DemoError> {
+    ColumnMapping::try_propose(Path::new(&path))
+}
+
+/// Create the time series table from the file at `path` using the column mapping proposed from its
+/// schema with the parts given in `overrides` replaced. The file is also selected as the data set
+/// that is ingested and the mapping that was used is returned.
+#[tauri::command]
+async fn create_table_from_dataset(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    path: String,
+    overrides: Option<ColumnMappingOverrides>,
+    error_bounds: ErrorBounds,
+    nullable_fields: Option<bool>,
+) -> Result<ColumnMapping, DemoError> {
+    let nullable_fields = nullable_fields.unwrap_or(false);
+    let path = PathBuf::from(path);
+    let mapping = ColumnMapping::try_propose(&path)?.with_overrides(overrides.unwrap_or_default());
+    let data_source = DataSource::File(Dataset::try_new(path, &mapping)?);
+
+    let manager_url = state.lock().await.topology.borrow().manager_url.clone();
+    create_time_series_table(
+        &app,
+        manager_url,
+        &data_source,
+        nullable_fields,
+        &error_bounds,
+    )
+    .await?;
+
+    let mut state = state.lock().await;
+    state.data_source = data_source;
+    state.nullable_fields = nullable_fields;
+
+    Ok(mapping)
+}
+
+/// Create the time series table for `data_source` in the ModelarDB cluster managed by the manager
+/// at `manager_url` with `error_bounds` for its field columns. The field columns are nullable if
+/// `nullable_fields` is set. The error bound chosen for each field column is emitted as an
+/// `error-bounds` event when the table has been created.
+async fn create_time_series_table<E: EventEmitter>(
+    emitter: &E,
+    manager_url: String,
+    data_source: &DataSource,
+    nullable_fields: bool,
+    error_bounds: &ErrorBounds,
+) -> Result<(), DemoError> {
+    let field_error_bounds = error_bounds.try_resolve(&data_source.field_column_names())?;
+    let modelardb_error_bounds = error_bounds::try_modelardb_error_bounds(&field_error_bounds)?;
+
+    let modelardb_manager_node = Node::Manager(manager_url.clone());
+    let mut modelardb_client = connect_to_modelardb(modelardb_manager_node).await?;
+
+    let table_schema = if nullable_fields {
+        data_source.nullable_table_schema()
+    } else {
+        data_source.table_schema()
+    };
+
+    let error_bound_table_type = TableType::TimeSeriesTable(
+        table_schema.as_ref().clone(),
+        modelardb_error_bounds,
+        HashMap::new(),
+    );
 
     modelardb_client
         .create(TABLE_NAME, error_bound_table_type)
         .await
-        .unwrap();
+        .map_err(|error| DemoError::FlightStatus(error.to_string()))?;
+
+    // The schema is recorded as an empty batch, so it is stored as Arrow IPC like the batches.
+    let recorded_schema = emitter.session_recorder().and_then(|session_recorder| {
+        session_recorder.record_batch(&RecordBatch::new_empty(table_schema))
+    });
+
+    if let Some(schema) = recorded_schema {
+        session::record(
+            emitter,
+            SessionEntry::CreateTable {
+                url: manager_url,
+                table_name: TABLE_NAME.to_owned(),
+                schema,
+                error_bounds: field_error_bounds.clone(),
+            },
+        );
+    }
+
+    emitter.emit_event("error-bounds", field_error_bounds);
+
+    Ok(())
 }
 
 #[tauri::command]
@@ -143,215 +400,846 @@ async fn ingest_into_table(
     app: AppHandle,
     state: State<'_, Mutex<AppState>>,
     count: usize,
-    comparison: String,
-) -> Result<(), String> {
-    let mut state = state.lock().await;
+    comparison: ComparisonFormat,
+    rate: Option<IngestionRate>,
+    options: Option<IngestionOptions>,
+) -> Result<(), DemoError> {
+    let state = state.lock().await;
 
-    if let Some(handle) = &state.ingestion_task {
-        handle.abort();
-    }
+    // The rate generator is shared by the instances of the task, so the rate profile continues
+    // from where it was when the task is resumed or restarted.
+    let rate = rate.unwrap_or_else(|| IngestionRate::constant(count));
+    let ingestion_source = IngestionSource {
+        data_source: state.data_source.clone(),
+        turbine_layout: state.turbine_layout.clone(),
+        nullable_fields: state.nullable_fields,
+    };
+
+    let options = options.unwrap_or_default();
+    check_ingestion(&ingestion_source, &rate, &options)?;
+    let rate_generator = Arc::new(std::sync::Mutex::new(RateGenerator::new(rate)));
+    // The written rows are counted from when the ingestion is started, like the offsets.
+    *state.written_rows.lock().unwrap() = WrittenRows::default();
+
+    let write_options = WriteOptions {
+        comparison,
+        fault_injector: state.fault_injector.clone(),
+        written_rows: state.written_rows.clone(),
+        modelardb_remote_object_store: state.modelardb_remote_object_store.clone(),
+        comparison_remote_object_store: state.comparison_remote_object_store.clone(),
+    };
+    let topology_receiver = state.topology.subscribe();
+    let health_receiver = state.node_health.subscribe();
 
-    let join_handle = tokio::spawn(ingest_into_table_task(app, count, comparison));
-    state.ingestion_task = Some(join_handle);
+    // Start from the beginning of the data set, while resuming continues from the current offset.
+    let ingestion_offsets = state.ingestion_offsets.clone();
+    ingestion_offsets.lock().unwrap().clear();
+
+    state.supervisor.start(INGESTION_TASK, move || {
+        ingest_into_table_task(
+            app.clone(),
+            topology_receiver.clone(),
+            health_receiver.clone(),
+            ingestion_source.clone(),
+            options.clone(),
+            ingestion_offsets.clone(),
+            rate_generator.clone(),
+            write_options.clone(),
+        )
+    });
 
     Ok(())
 }
 
-async fn ingest_into_table_task(app: AppHandle, count: usize, comparison: String) {
-    let resource_path = app
-        .path()
-        .resolve("resources/wind_cleaned.parquet", BaseDirectory::Resource)
-        .unwrap();
-
-    let file = tokio::fs::File::open(resource_path).await.unwrap();
-    let builder = ParquetRecordBatchStreamBuilder::new(file).await.unwrap();
+#[allow(clippy::too_many_arguments)]
+async fn ingest_into_table_task<E: EventEmitter>(
+    emitter: E,
+    mut topology_receiver: watch::Receiver<Topology>,
+    health_receiver: watch::Receiver<HealthMap>,
+    ingestion_source: IngestionSource,
+    options: IngestionOptions,
+    ingestion_offsets: IngestionOffsets,
+    rate_generator: Arc<std::sync::Mutex<RateGenerator>>,
+    write_options: WriteOptions,
+) -> Result<(), DemoError> {
+    let data_source = &ingestion_source.data_source;
+    let num_rows = match data_source {
+        DataSource::File(dataset) => dataset.try_num_rows().await?,
+        DataSource::Synthetic(_) => 0,
+    };
+    let replay = rate_generator.lock().unwrap().replay();
+    let IngestionOptions {
+        backpressure,
+        disorder,
+        dropout,
+        passes,
+    } = options;
+    let table_schema = ingestion_source.table_schema();
 
-    let stream = builder.build().unwrap();
-    let record_batches = stream.try_collect::<Vec<_>>().await.unwrap();
-    let record_batch =
-        compute::concat_batches(&record_batches[0].schema(), &record_batches).unwrap();
+    let mut node_ingestions: Vec<NodeIngestion<E>> = vec![];
+    let mut offsets = ingestion_offsets.lock().unwrap().clone();
 
-    let edge_nodes = util::edge_nodes();
-    let edge_clients = util::connect_to_nodes(edge_nodes).await;
+    // The batches held back for the edge nodes that are replaced while the topology changes, with
+    // the URL of the edge node they were held back for.
+    let mut late_batches = vec![];
 
-    let mut offset = 0;
+    // The totals of the edge nodes that have been ingested into, including removed edge nodes.
+    let mut node_totals: Vec<NodeTotals> = vec![];
 
-    let mut node_record_batches = vec![];
-    for node_index in 0..NODE_COUNT {
-        let node_record_batch = record_batch.slice((30000 * node_index) as usize, 30000);
-        node_record_batches.push(node_record_batch);
-    }
+    // Mark the topology as changed so the edge nodes are connected to in the first iteration.
+    topology_receiver.mark_changed();
 
     loop {
-        for (index, (modelardb_client, comparison_client)) in edge_clients.iter().enumerate() {
-            tokio::spawn(ingest_data_points_into_nodes(
-                app.clone(),
-                modelardb_client.clone(),
-                comparison_client.clone(),
-                index,
-                node_record_batches[index].slice(offset, count),
-                comparison.clone(),
-            ));
+        // If edge nodes have been added or removed, reconnect and redistribute the data set.
+        // The topology is only dropped when the app is closed, so the task can stop.
+        let Ok(topology_changed) = topology_receiver.has_changed() else {
+            return Ok(());
+        };
+
+        if topology_changed {
+            let edge_nodes = topology_receiver.borrow_and_update().edge_nodes.clone();
+            let num_nodes = edge_nodes.len();
+
+            // The partitions change with the number of edge nodes, so each edge node continues from
+            // where the rows of its new partition were read up to.
+            offsets = rebalance_offsets(&offsets, num_rows, num_nodes);
+            late_batches.extend(node_ingestions.iter_mut().map(|node_ingestion| {
+                let url = node_ingestion.url.clone();
+                (url, node_ingestion.disorder.take_late_batches())
+            }));
+
+            // Split the data set evenly between the edge nodes. Without a layout, each edge node
+            // ingests the data set as one turbine or an even share of the synthetic turbines.
+            let rows_per_node = num_rows / num_nodes.max(1);
+            let default_turbines = match data_source {
+                DataSource::File(_) => num_nodes,
+                DataSource::Synthetic(config) => config.turbines,
+            };
+
+            let turbines_per_node = ingestion_source
+                .turbine_layout
+                .turbines_per_node(num_nodes, default_turbines);
+
+            node_ingestions = edge_nodes
+                .into_iter()
+                .zip(turbines_per_node)
+                .enumerate()
+                .map(
+                    |(node_index, ((modelardb_node, comparison_node), turbines))| {
+                        let source = match data_source {
+                            DataSource::File(dataset) => {
+                                let start = rows_per_node * node_index;
+                                let offset = offsets.get(node_index).copied().unwrap_or(0);
+                                NodeSource::Partition(PartitionReader::new(
+                                    dataset.clone(),
+                                    start,
+                                    start + rows_per_node,
+                                    offset,
+                                    passes,
+                                ))
+                            }
+                            DataSource::Synthetic(config) => NodeSource::Synthetic(
+                                TurbineGenerator::new(config, turbines.len(), node_index),
+                            ),
+                        };
+
+                        let writer = NodeWriter::new(
+                            emitter.clone(),
+                            modelardb_node.url().to_owned(),
+                            comparison_node.url().to_owned(),
+                            backpressure,
+                            write_options.clone(),
+                        );
+
+                        NodeIngestion {
+                            url: modelardb_node.url().to_owned(),
+                            turbines,
+                            writer,
+                            dropout: DropoutGenerator::new(
+                                &dropout,
+                                table_schema.clone(),
+                                node_index,
+                            ),
+                            disorder: DisorderGenerator::new(&disorder, node_index),
+                            edge_connection: EdgeConnection::new(modelardb_node, comparison_node),
+                            source,
+                            next_timestamp: None,
+                            replay_clock: None,
+                        }
+                    },
+                )
+                .collect();
+
+            // The held back batches are written to the same edge node if it is still there, and
+            // spread over the other edge nodes otherwise.
+            if !node_ingestions.is_empty() {
+                for (index, (url, batches)) in late_batches.drain(..).enumerate() {
+                    let node_index = node_ingestions
+                        .iter()
+                        .position(|node_ingestion| node_ingestion.url == url)
+                        .unwrap_or(index % node_ingestions.len());
+
+                    node_ingestions[node_index]
+                        .disorder
+                        .add_late_batches(batches);
+                }
+            }
         }
 
-        offset += count;
+        let node_health = health_receiver.borrow().clone();
+        let counts = rate_generator
+            .lock()
+            .unwrap()
+            .next_tick(node_ingestions.len());
+
+        let mut ingested = false;
+        for (node_ingestion, count) in node_ingestions.iter_mut().zip(counts) {
+            let clients = node_ingestion.edge_connection.clients(&node_health).await;
+
+            let record_batch = node_ingestion
+                .try_next_record_batch(count, replay, &rate_generator, clients.is_some())
+                .await?;
+
+            // Skip the edge nodes that are down or that could not be reconnected to yet.
+            let (Some((modelardb_client, comparison_client)), Some(record_batch)) =
+                (clients, record_batch)
+            else {
+                continue;
+            };
+
+            let totals = match node_totals
+                .iter()
+                .position(|totals| totals.url == node_ingestion.url)
+            {
+                Some(index) => &mut node_totals[index],
+                None => {
+                    node_totals.push(NodeTotals {
+                        url: node_ingestion.url.clone(),
+                        ..NodeTotals::default()
+                    });
+                    node_totals.last_mut().unwrap()
+                }
+            };
+            totals.rows += record_batch.num_rows();
+            totals.batches += usize::from(record_batch.num_rows() > 0);
+
+            let record_batch = node_ingestion.dropout.apply(record_batch)?;
+
+            // The batches are written by the edge node's worker, so a slow edge node does not delay
+            // the others unless its writer blocks because too many batches are in flight.
+            for (record_batch, kind) in node_ingestion.disorder.next_batches(record_batch)? {
+                node_ingestion
+                    .writer
+                    .write(WriteRequest {
+                        modelardb_client: modelardb_client.clone(),
+                        comparison_client: comparison_client.clone(),
+                        record_batch,
+                        kind,
+                    })
+                    .await;
+            }
 
-        if offset + count > 30000 {
-            offset = 0;
+            ingested = true;
         }
 
-        time::sleep(Duration::from_secs(1)).await;
+        offsets = node_ingestions
+            .iter()
+            .map(|node_ingestion| match &node_ingestion.source {
+                NodeSource::Partition(partition_reader) => partition_reader.offset(),
+                NodeSource::Synthetic(_) => 0,
+            })
+            .collect();
+
+        *ingestion_offsets.lock().unwrap() = offsets.clone();
+
+        // A finite run completes when all edge nodes have read their partitions.
+        if passes.is_some()
+            && !node_ingestions.is_empty()
+            && node_ingestions.iter().all(NodeIngestion::is_finished)
+        {
+            let edge_nodes = topology_receiver.borrow().edge_nodes.clone();
+            let node_health = health_receiver.borrow().clone();
+
+            // The run is over, so the next ingestion starts from the beginning of the data set.
+            ingestion_offsets.lock().unwrap().clear();
+
+            return complete_ingestion(
+                &emitter,
+                &mut node_ingestions,
+                &edge_nodes,
+                &node_health,
+                node_totals,
+                &write_options,
+            )
+            .await;
+        }
+
+        // When replaying as fast as possible, continue as soon as the rows have been ingested.
+        if replay.is_some_and(|replay| replay.is_unpaced()) && ingested {
+            for node_ingestion in &node_ingestions {
+                node_ingestion.writer.written().await;
+            }
+        } else {
+            time::sleep(INGESTION_INTERVAL).await;
+        }
     }
 }
 
-#[derive(Clone, Serialize)]
-struct IngestedSize {
-    table_name: String,
-    size: usize,
+/// Where the rows ingested into the edge nodes come from, which turbines they are from, and if the
+/// table they are ingested into has nullable field columns.
+#[derive(Clone)]
+struct IngestionSource {
+    data_source: DataSource,
+    turbine_layout: TurbineLayout,
+    nullable_fields: bool,
 }
 
-async fn ingest_data_points_into_nodes(
-    app: AppHandle,
-    mut modelardb_client: Client,
-    mut comparison_client: FlightServiceClient<Channel>,
-    node_id: usize,
-    data_points: RecordBatch,
-    comparison: String,
-) {
-    let mut timestamps = TimestampBuilder::with_capacity(data_points.num_rows());
+impl IngestionSource {
+    /// Schema of the table the rows are ingested into.
+    fn table_schema(&self) -> SchemaRef {
+        if self.nullable_fields {
+            self.data_source.nullable_table_schema()
+        } else {
+            self.data_source.table_schema()
+        }
+    }
+}
 
-    let start = SystemTime::now();
-    let since_the_epoch = start.duration_since(UNIX_EPOCH).unwrap();
+/// How the rows are ingested into the edge nodes apart from how many are ingested per second.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct IngestionOptions {
+    /// How many batches may be in flight to each edge node and what happens to the batches for an
+    /// edge node that cannot keep up.
+    backpressure: Backpressure,
+    /// Shuffle, duplicate, and delay the batches ingested into each edge node.
+    disorder: Disorder,
+    /// Null or remove the values of the sensors that drop out in each turbine.
+    dropout: Dropout,
+    /// Stop after each edge node has read its partition of the data set this many times, flush
+    /// the nodes, and emit an `ingestion-completed` event. Defaults to starting over from the
+    /// beginning of the partitions until the ingestion is stopped.
+    passes: Option<usize>,
+}
 
-    let mut next_timestamp: i64 = since_the_epoch.as_micros() as i64;
-    let step = (Duration::from_secs(2).as_micros() as i64) / (data_points.num_rows() as i64);
+/// Where the rows ingested into an edge node come from.
+enum NodeSource {
+    Partition(PartitionReader),
+    Synthetic(TurbineGenerator),
+}
+
+/// The state of the ingestion into an edge node.
+struct NodeIngestion<E: EventEmitter> {
+    /// URL of the ModelarDB edge node.
+    url: String,
+    /// The turbines the rows are from, which are assigned to the rows in turn.
+    turbines: Vec<TurbineId>,
+    writer: NodeWriter<E>,
+    /// Nulls or removes the values of the sensors that are dropped out.
+    dropout: DropoutGenerator,
+    /// Shuffles, duplicates, and holds back the batches before they are written.
+    disorder: DisorderGenerator,
+    edge_connection: EdgeConnection,
+    source: NodeSource,
+    /// The timestamp after the last generated timestamp.
+    next_timestamp: Option<i64>,
+    replay_clock: Option<ReplayClock>,
+}
 
-    for _ in 0..data_points.num_rows() {
-        timestamps.append_value(next_timestamp);
-        next_timestamp += step;
+impl<E: EventEmitter> NodeIngestion<E> {
+    /// If the edge node has read all of the passes over its partition of the data set, or has no
+    /// turbines to ingest rows for.
+    fn is_finished(&self) -> bool {
+        let partition_finished = match &self.source {
+            NodeSource::Partition(partition_reader) => partition_reader.is_finished(),
+            NodeSource::Synthetic(_) => false,
+        };
+
+        self.turbines.is_empty() || partition_finished
     }
 
-    let park_id = if node_id < 5 { "park_1" } else { "park_2" };
-    let windmill_id = format!("windmill_{}", node_id + 1);
+    /// Return the next `count` rows to ingest into the edge node with the columns of the table, or
+    /// [`None`] if there are no rows to ingest because `ingest` is false or the edge node has no
+    /// turbines. If `ingest` is false, the rows of a data set are skipped as if they were ingested.
+    async fn try_next_record_batch(
+        &mut self,
+        count: usize,
+        replay: Option<Replay>,
+        rate_generator: &std::sync::Mutex<RateGenerator>,
+        ingest: bool,
+    ) -> Result<Option<RecordBatch>, DemoError> {
+        let num_turbines = self.turbines.len();
+        if num_turbines == 0 {
+            return Ok(None);
+        }
 
-    let park_id_array: StringArray = iter::repeat(Some(park_id))
-        .take(data_points.num_rows())
-        .collect();
+        match &mut self.source {
+            NodeSource::Partition(partition_reader) => {
+                let rows = match replay {
+                    Some(replay) => {
+                        replay
+                            .try_next(partition_reader, &mut self.replay_clock, count)
+                            .await?
+                    }
+                    None if ingest => partition_reader.try_next(count).await?,
+                    None => {
+                        partition_reader.skip(count);
+                        return Ok(None);
+                    }
+                };
+
+                if !ingest {
+                    return Ok(None);
+                }
+
+                let dataset = partition_reader.dataset();
+                let (source_timestamps, data_points) = dataset.split_timestamps(&rows)?;
+
+                // The turbines of the edge node share the generated timestamps, so each row is from
+                // the next turbine at the same time as the previous row until all have a row.
+                let num_rows = data_points.num_rows();
+                let timestamps = match (&self.replay_clock, source_timestamps) {
+                    (Some(replay_clock), Some(source_timestamps)) => {
+                        replay_clock.shift_timestamps(&source_timestamps)
+                    }
+                    _ => {
+                        let timestamps = rate_generator
+                            .lock()
+                            .unwrap()
+                            .timestamps(&mut self.next_timestamp, num_rows.div_ceil(num_turbines));
+
+                        repeat_timestamps(&timestamps, num_turbines).slice(0, num_rows)
+                    }
+                };
+
+                let record_batch = table_record_batch(
+                    dataset.table_schema(),
+                    &self.turbines,
+                    timestamps,
+                    &data_points,
+                )?;
+
+                Ok(Some(record_batch))
+            }
+            NodeSource::Synthetic(turbine_generator) => {
+                if !ingest {
+                    return Ok(None);
+                }
+
+                // The rows ingested into the edge node are split between its turbines.
+                let timestamps = rate_generator
+                    .lock()
+                    .unwrap()
+                    .timestamps(&mut self.next_timestamp, count.div_ceil(num_turbines));
+
+                let data_points = turbine_generator.next_data_points(&timestamps)?;
+
+                let record_batch = table_record_batch(
+                    generator::table_schema(),
+                    &self.turbines,
+                    repeat_timestamps(&timestamps, num_turbines),
+                    &data_points,
+                )?;
+
+                Ok(Some(record_batch))
+            }
+        }
+    }
+}
 
-    let windmill_id_array: StringArray = iter::repeat(Some(&windmill_id))
-        .take(data_points.num_rows())
-        .collect();
+/// The rows read from the partition of an edge node and written to it during a run.
+#[derive(Clone, Default, Serialize)]
+struct NodeTotals {
+    /// URL of the ModelarDB edge node.
+    url: String,
+    rows: usize,
+    batches: usize,
+    /// The number of times the edge node read all of its partition of the data set.
+    passes: usize,
+}
 
-    let record_batch = RecordBatch::try_new(
-        Arc::new(util::table_schema()),
-        vec![
-            Arc::new(timestamps.finish()),
-            Arc::new(park_id_array),
-            Arc::new(windmill_id_array),
-            data_points.column(0).clone(),
-            data_points.column(1).clone(),
-            data_points.column(2).clone(),
-            data_points.column(3).clone(),
-            data_points.column(4).clone(),
-            data_points.column(5).clone(),
-            data_points.column(6).clone(),
-            data_points.column(7).clone(),
-            data_points.column(8).clone(),
-            data_points.column(9).clone(),
-        ],
-    )
-    .unwrap();
+#[derive(Clone, Serialize)]
+struct IngestionCompleted {
+    nodes: Vec<NodeTotals>,
+    written_rows: WrittenRows,
+}
 
-    // One 8-byte timestamp, two 4-byte tags, and ten 4-byte fields per row.
-    let ingested_size = (8 + (12 * 4)) * data_points.num_rows();
+/// Write the batches that are held back, wait until all batches have been written to the
+/// `edge_nodes`, flush them, and emit an `ingestion-completed` event with `node_totals` and the
+/// rows written to each system.
+async fn complete_ingestion<E: EventEmitter>(
+    emitter: &E,
+    node_ingestions: &mut [NodeIngestion<E>],
+    edge_nodes: &[(Node, Node)],
+    node_health: &HealthMap,
+    mut node_totals: Vec<NodeTotals>,
+    write_options: &WriteOptions,
+) -> Result<(), DemoError> {
+    for node_ingestion in node_ingestions.iter_mut() {
+        if let Some((modelardb_client, comparison_client)) =
+            node_ingestion.edge_connection.clients(node_health).await
+        {
+            for (record_batch, kind) in node_ingestion.disorder.held_back_batches() {
+                node_ingestion
+                    .writer
+                    .write(WriteRequest {
+                        modelardb_client: modelardb_client.clone(),
+                        comparison_client: comparison_client.clone(),
+                        record_batch,
+                        kind,
+                    })
+                    .await;
+            }
+        }
 
-    app.emit(
-        "data-ingested",
-        IngestedSize {
-            table_name: TABLE_NAME.to_owned(),
-            size: ingested_size,
+        node_ingestion.writer.written().await;
+
+        if let NodeSource::Partition(partition_reader) = &node_ingestion.source {
+            for totals in &mut node_totals {
+                if totals.url == node_ingestion.url {
+                    totals.passes = partition_reader.passes();
+                }
+            }
+        }
+    }
+
+    // Flush both systems so the final size of the tables includes all of the ingested rows.
+    for (modelardb_node, comparison_node) in edge_nodes {
+        if health::is_available(node_health, modelardb_node.url()) {
+            flush_modelardb_node_and_emit_remote_object_store_table_size(
+                emitter.clone(),
+                modelardb_node.clone(),
+                write_options.modelardb_remote_object_store.clone(),
+                true,
+                write_options.fault_injector.clone(),
+            )
+            .await?;
+        }
+
+        if health::is_available(node_health, comparison_node.url()) {
+            flush_comparison_node_and_emit_remote_object_store_table_size(
+                emitter.clone(),
+                comparison_node.clone(),
+                write_options.comparison_remote_object_store.clone(),
+                write_options.fault_injector.clone(),
+            )
+            .await?;
+        }
+    }
+
+    let written_rows = write_options.written_rows.lock().unwrap().clone();
+    emitter.emit_event(
+        "ingestion-completed",
+        IngestionCompleted {
+            nodes: node_totals,
+            written_rows,
         },
+    );
+
+    Ok(())
+}
+
+/// Repeat each of `timestamps` `count` times in a row.
+fn repeat_timestamps(timestamps: &TimestampArray, count: usize) -> TimestampArray {
+    TimestampArray::from_iter_values(
+        timestamps
+            .values()
+            .iter()
+            .flat_map(|timestamp| iter::repeat(*timestamp).take(count)),
     )
-    .unwrap();
+}
 
-    modelardb_client
-        .write(TABLE_NAME, record_batch.clone())
-        .await
-        .unwrap();
+/// Map the `offsets` of the edge nodes into the previous partitions of the `num_rows` rows in the
+/// data set, one per offset, to offsets into the partitions of `num_nodes` edge nodes. Each new
+/// partition continues from the row the edge node whose partition contained its first row had
+/// reached, in the same pass, so rows are not skipped when the partitions are split or merged.
+fn rebalance_offsets(offsets: &[usize], num_rows: usize, num_nodes: usize) -> Vec<usize> {
+    let old_rows_per_node = num_rows / offsets.len().max(1);
+    let rows_per_node = num_rows / num_nodes.max(1);
 
-    let record_batch_bytes = util::try_convert_record_batch_to_bytes(&record_batch);
-    let action = Action {
-        r#type: format!(
-            "IngestData{}",
-            comparison[..1].to_uppercase() + &comparison[1..]
-        ),
-        body: record_batch_bytes.into(),
-    };
+    if offsets.is_empty() || old_rows_per_node == 0 || rows_per_node == 0 {
+        return vec![0; num_nodes];
+    }
+
+    (0..num_nodes)
+        .map(|node_index| {
+            let start = rows_per_node * node_index;
+            let old_node_index = (start / old_rows_per_node).min(offsets.len() - 1);
+            let old_offset = offsets[old_node_index];
+
+            let pass = old_offset / old_rows_per_node;
+            let row = old_rows_per_node * old_node_index + old_offset % old_rows_per_node;
 
-    comparison_client.do_action(action).await.unwrap();
+            pass * rows_per_node + row.saturating_sub(start).min(rows_per_node)
+        })
+        .collect()
 }
 
-#[tauri::command]
-async fn flush_nodes(app: AppHandle, state: State<'_, Mutex<AppState>>) -> Result<(), String> {
-    let mut state = state.lock().await;
+/// Check that `ingestion_source` can be ingested at `rate` with `options`, i.e., that it has
+/// timestamps if it is replayed, that it is a data set if the run is finite, and that the table has
+/// the fields that drop out.
+fn check_ingestion(
+    ingestion_source: &IngestionSource,
+    rate: &IngestionRate,
+    options: &IngestionOptions,
+) -> Result<(), DemoError> {
+    if rate.replay.is_some() && !ingestion_source.data_source.has_timestamps() {
+        return Err(DemoError::Configuration(
+            "The data set can only be replayed if its column mapping has a timestamp column."
+                .to_owned(),
+        ));
+    }
 
-    if let Some(handle) = &state.flush_modelardb_task {
-        handle.abort();
+    if options.passes.is_some() && matches!(ingestion_source.data_source, DataSource::Synthetic(_))
+    {
+        return Err(DemoError::Configuration(
+            "A finite number of passes can only be ingested from a data set.".to_owned(),
+        ));
     }
 
-    let join_handle = tokio::spawn(flush_modelardb_nodes_task(
-        app.clone(),
-        state.modelardb_remote_object_store.clone(),
-    ));
+    options.dropout.check(&ingestion_source.table_schema())
+}
+
+/// Add the `timestamps` and the tags identifying the `turbines` to `data_points`. The rows are
+/// assigned to the turbines in turn.
+fn table_record_batch(
+    table_schema: SchemaRef,
+    turbines: &[TurbineId],
+    timestamps: TimestampArray,
+    data_points: &RecordBatch,
+) -> Result<RecordBatch, DemoError> {
+    let row_turbines = || turbines.iter().cycle().take(data_points.num_rows());
+
+    let park_id_array: StringArray = row_turbines()
+        .map(|turbine| Some(turbine.park_id.as_str()))
+        .collect();
+
+    let windmill_id_array: StringArray = row_turbines()
+        .map(|turbine| Some(turbine.windmill_id.as_str()))
+        .collect();
 
-    state.flush_modelardb_task = Some(join_handle);
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(timestamps),
+        Arc::new(park_id_array),
+        Arc::new(windmill_id_array),
+    ];
+    columns.extend(data_points.columns().iter().cloned());
+
+    Ok(RecordBatch::try_new(table_schema, columns)?)
+}
 
-    if let Some(handle) = &state.flush_comparison_task {
-        handle.abort();
+/// Write the batch in `request` to the ModelarDB edge node with `modelardb_url` and the
+/// comparison node with `comparison_url` and emit a `data-ingested` event with its size. The faults
+/// that are active for each node are injected into the write to it. The batch is written to both
+/// nodes even if the write to one of them fails, so a failure in one system does not also remove
+/// the rows from the other.
+async fn ingest_data_points_into_nodes<E: EventEmitter>(
+    emitter: E,
+    write_options: &WriteOptions,
+    modelardb_url: &str,
+    comparison_url: &str,
+    request: WriteRequest,
+) -> Result<(), DemoError> {
+    let WriteRequest {
+        mut modelardb_client,
+        mut comparison_client,
+        record_batch,
+        kind,
+    } = request;
+
+    if record_batch.num_rows() == 0 {
+        return Ok(());
     }
 
-    let join_handle = tokio::spawn(flush_comparison_nodes_task(
-        app,
-        state.comparison_remote_object_store.clone(),
-    ));
+    emitter.emit_event(
+        "data-ingested",
+        IngestedSize::try_new(TABLE_NAME, modelardb_url, &record_batch)?,
+    );
+
+    // The batch is stored once in a session that is being recorded and referenced by both writes.
+    let recorded_batch = emitter
+        .session_recorder()
+        .and_then(|session_recorder| session_recorder.record_batch(&record_batch));
+
+    let fault_injector = &write_options.fault_injector;
+
+    let modelardb_result = async {
+        if fault_injector
+            .inject(
+                &emitter,
+                modelardb_url,
+                Operation::Write,
+                Some(&record_batch),
+            )
+            .await?
+        {
+            modelardb_client
+                .write(TABLE_NAME, record_batch.clone())
+                .await
+                .map_err(|error| DemoError::FlightStatus(error.to_string()))?;
+
+            if let Some(batch) = recorded_batch {
+                session::record(
+                    &emitter,
+                    SessionEntry::ModelardbWrite {
+                        url: modelardb_url.to_owned(),
+                        table_name: TABLE_NAME.to_owned(),
+                        batch,
+                    },
+                );
+            }
+
+            let mut written_rows = write_options.written_rows.lock().unwrap();
+            written_rows.modelardb.add(kind, record_batch.num_rows());
+        }
+
+        Ok::<(), DemoError>(())
+    }
+    .await;
+
+    let comparison_result = async {
+        if fault_injector
+            .inject(
+                &emitter,
+                comparison_url,
+                Operation::Write,
+                Some(&record_batch),
+            )
+            .await?
+        {
+            let record_batch_bytes = util::try_convert_record_batch_to_bytes(&record_batch)?;
+            let action = Action {
+                r#type: write_options.comparison.ingest_action().to_owned(),
+                body: record_batch_bytes.into(),
+            };
+            let action_type = action.r#type.clone();
+
+            comparison_client.do_action(action).await?;
+
+            if let Some(batch) = recorded_batch {
+                session::record(
+                    &emitter,
+                    SessionEntry::ComparisonWrite {
+                        url: comparison_url.to_owned(),
+                        action: action_type,
+                        batch,
+                    },
+                );
+            }
+
+            let mut written_rows = write_options.written_rows.lock().unwrap();
+            written_rows.comparison.add(kind, record_batch.num_rows());
+        }
+
+        Ok::<(), DemoError>(())
+    }
+    .await;
+
+    match (modelardb_result, comparison_result) {
+        // Both errors are reported, as they can have different causes.
+        (Err(modelardb_error), Err(comparison_error)) => {
+            events::emit_task_error(&emitter, INGESTION_TASK, modelardb_error);
+            Err(comparison_error)
+        }
+        (modelardb_result, comparison_result) => modelardb_result.and(comparison_result),
+    }
+}
+
+#[tauri::command]
+async fn flush_nodes(app: AppHandle, state: State<'_, Mutex<AppState>>) -> Result<(), DemoError> {
+    let state = state.lock().await;
+
+    let topology_receiver = state.topology.subscribe();
+    let health_receiver = state.node_health.subscribe();
+    let modelardb_remote_object_store = state.modelardb_remote_object_store.clone();
+    let fault_injector = state.fault_injector.clone();
+    let emitter = app.clone();
+
+    state.supervisor.start(FLUSH_MODELARDB_TASK, move || {
+        flush_modelardb_nodes_task(
+            emitter.clone(),
+            topology_receiver.clone(),
+            health_receiver.clone(),
+            modelardb_remote_object_store.clone(),
+            fault_injector.clone(),
+        )
+        .map(Ok)
+    });
 
-    state.flush_comparison_task = Some(join_handle);
+    let topology_receiver = state.topology.subscribe();
+    let health_receiver = state.node_health.subscribe();
+    let comparison_remote_object_store = state.comparison_remote_object_store.clone();
+    let fault_injector = state.fault_injector.clone();
+
+    state.supervisor.start(FLUSH_COMPARISON_TASK, move || {
+        flush_comparison_nodes_task(
+            app.clone(),
+            topology_receiver.clone(),
+            health_receiver.clone(),
+            comparison_remote_object_store.clone(),
+            fault_injector.clone(),
+        )
+        .map(Ok)
+    });
 
     Ok(())
 }
 
-async fn flush_modelardb_nodes_task(app: AppHandle, modelardb_remote_object_store: AmazonS3) {
-    let edge_nodes = util::edge_nodes();
+async fn flush_modelardb_nodes_task<E: EventEmitter>(
+    emitter: E,
+    topology_receiver: watch::Receiver<Topology>,
+    health_receiver: watch::Receiver<HealthMap>,
+    modelardb_remote_object_store: Arc<dyn ObjectStore>,
+    fault_injector: Arc<FaultInjector>,
+) {
     let mut iteration_counter = 0;
 
     loop {
+        let edge_nodes = topology_receiver.borrow().edge_nodes.clone();
+
+        if edge_nodes.is_empty() {
+            time::sleep(Duration::from_secs(2)).await;
+        }
+
         let flush_modelardb_node = iteration_counter % 4 == 0;
         iteration_counter = iteration_counter + 1;
 
         for (modelardb_node, _comparison_node) in &edge_nodes {
-            tokio::spawn(
-                flush_modelardb_node_and_emit_remote_object_store_table_size(
-                    app.clone(),
-                    modelardb_node.clone(),
-                    modelardb_remote_object_store.clone(),
-                    flush_modelardb_node,
-                ),
-            );
+            // Skip the node if it is down, it is flushed again in the next iteration.
+            if health::is_available(&health_receiver.borrow(), modelardb_node.url()) {
+                tokio::spawn(events::report_task_error(
+                    emitter.clone(),
+                    FLUSH_MODELARDB_TASK,
+                    flush_modelardb_node_and_emit_remote_object_store_table_size(
+                        emitter.clone(),
+                        modelardb_node.clone(),
+                        modelardb_remote_object_store.clone(),
+                        flush_modelardb_node,
+                        fault_injector.clone(),
+                    ),
+                ));
+            }
 
             time::sleep(Duration::from_secs(2)).await;
         }
     }
 }
 
-async fn flush_modelardb_node_and_emit_remote_object_store_table_size(
-    app: AppHandle,
+async fn flush_modelardb_node_and_emit_remote_object_store_table_size<E: EventEmitter>(
+    emitter: E,
     node: Node,
-    object_store: AmazonS3,
+    object_store: Arc<dyn ObjectStore>,
     flush_node: bool,
-) {
-    let mut flight_client = FlightServiceClient::connect(node.url().to_owned())
-        .await
-        .unwrap();
+    fault_injector: Arc<FaultInjector>,
+) -> Result<(), DemoError> {
+    if !fault_injector
+        .inject(&emitter, node.url(), Operation::Flush, None)
+        .await?
+    {
+        return Ok(());
+    }
+
+    let mut flight_client = FlightServiceClient::connect(node.url().to_owned()).await?;
 
     let action_type = if flush_node {
         "FlushNode"
@@ -364,64 +1252,119 @@ async fn flush_modelardb_node_and_emit_remote_object_store_table_size(
         body: vec![].into(),
     };
 
-    flight_client.do_action(action.clone()).await.unwrap();
+    flight_client.do_action(action.clone()).await?;
+
+    session::record(
+        &emitter,
+        SessionEntry::Flush {
+            url: node.url().to_owned(),
+            system: System::Modelardb,
+            action: action_type.to_owned(),
+            table_name: None,
+        },
+    );
 
     if flush_node {
-        app.emit("flushing-modelardb-node", node.url()).unwrap();
+        emitter.emit_event("flushing-modelardb-node", node.url());
 
         // Vacuum the node to remove any deleted data.
         flight_client
             .do_get(Ticket::new("VACUUM".to_owned()))
-            .await
-            .unwrap();
+            .await?;
+
+        session::record(
+            &emitter,
+            SessionEntry::Vacuum {
+                url: node.url().to_owned(),
+            },
+        );
 
         emit_remote_object_store_table_size(
-            app.clone(),
+            emitter.clone(),
             object_store.clone(),
             "modelardb".to_owned(),
         )
-        .await;
+        .await?;
     }
-}
 
-async fn flush_comparison_nodes_task(app: AppHandle, comparison_remote_object_store: AmazonS3) {
-    let edge_nodes = util::edge_nodes();
+    Ok(())
+}
 
+async fn flush_comparison_nodes_task<E: EventEmitter>(
+    emitter: E,
+    topology_receiver: watch::Receiver<Topology>,
+    health_receiver: watch::Receiver<HealthMap>,
+    comparison_remote_object_store: Arc<dyn ObjectStore>,
+    fault_injector: Arc<FaultInjector>,
+) {
     loop {
+        let edge_nodes = topology_receiver.borrow().edge_nodes.clone();
+
+        if edge_nodes.is_empty() {
+            time::sleep(Duration::from_secs(1)).await;
+        }
+
         for (_modelardb_node, comparison_node) in &edge_nodes {
-            tokio::spawn(
-                flush_comparison_node_and_emit_remote_object_store_table_size(
-                    app.clone(),
-                    comparison_node.clone(),
-                    comparison_remote_object_store.clone(),
-                ),
-            );
+            // Skip the node if it is down, it is flushed again in the next iteration.
+            if health::is_available(&health_receiver.borrow(), comparison_node.url()) {
+                tokio::spawn(events::report_task_error(
+                    emitter.clone(),
+                    FLUSH_COMPARISON_TASK,
+                    flush_comparison_node_and_emit_remote_object_store_table_size(
+                        emitter.clone(),
+                        comparison_node.clone(),
+                        comparison_remote_object_store.clone(),
+                        fault_injector.clone(),
+                    ),
+                ));
+            }
 
             time::sleep(Duration::from_secs(1)).await;
         }
     }
 }
 
-async fn flush_comparison_node_and_emit_remote_object_store_table_size(
-    app: AppHandle,
+async fn flush_comparison_node_and_emit_remote_object_store_table_size<E: EventEmitter>(
+    emitter: E,
     node: Node,
-    object_store: AmazonS3,
-) {
-    let mut flight_client = FlightServiceClient::connect(node.url().to_owned())
-        .await
-        .unwrap();
+    object_store: Arc<dyn ObjectStore>,
+    fault_injector: Arc<FaultInjector>,
+) -> Result<(), DemoError> {
+    if !fault_injector
+        .inject(&emitter, node.url(), Operation::Flush, None)
+        .await?
+    {
+        return Ok(());
+    }
 
+    let mut flight_client = FlightServiceClient::connect(node.url().to_owned()).await?;
+
+    // The comparison node flushes the files to the directory of the table in the object store.
     let action = Action {
         r#type: "FlushNode".to_owned(),
-        body: vec![].into(),
+        body: TABLE_NAME.as_bytes().to_vec().into(),
     };
 
-    app.emit("flushing-comparison-node", node.url()).unwrap();
+    emitter.emit_event("flushing-comparison-node", node.url());
+
+    flight_client.do_action(action.clone()).await?;
 
-    flight_client.do_action(action.clone()).await.unwrap();
+    session::record(
+        &emitter,
+        SessionEntry::Flush {
+            url: node.url().to_owned(),
+            system: System::Comparison,
+            action: action.r#type,
+            table_name: Some(TABLE_NAME.to_owned()),
+        },
+    );
 
-    emit_remote_object_store_table_size(app.clone(), object_store.clone(), "comparison".to_owned())
-        .await;
+    emit_remote_object_store_table_size(
+        emitter.clone(),
+        object_store.clone(),
+        "comparison".to_owned(),
+    )
+    .await
 }
 
 #[derive(Clone, Serialize)]
@@ -430,21 +1373,22 @@ struct RemoteObjectStoreTableSize {
     table_size: u64,
 }
 
-async fn emit_remote_object_store_table_size(
-    app: AppHandle,
-    object_store: AmazonS3,
+async fn emit_remote_object_store_table_size<E: EventEmitter>(
+    emitter: E,
+    object_store: Arc<dyn ObjectStore>,
     node_type: String,
-) {
-    let table_size = util::tables_size(&object_store).await;
+) -> Result<(), DemoError> {
+    let table_size = util::tables_size(object_store.as_ref()).await?;
 
-    app.emit(
+    emitter.emit_event(
         "remote-object-store-size",
         RemoteObjectStoreTableSize {
             node_type: node_type.clone(),
             table_size,
         },
-    )
-    .unwrap();
+    );
+
+    Ok(())
 }
 
 #[tauri::command]
@@ -452,32 +1396,42 @@ async fn monitor_nodes(
     app: AppHandle,
     state: State<'_, Mutex<AppState>>,
     interval_seconds: u64,
-) -> Result<(), String> {
-    let mut state = state.lock().await;
-
-    if let Some(handle) = &state.monitor_nodes_task {
-        handle.abort();
-    }
+) -> Result<(), DemoError> {
+    let state = state.lock().await;
+    let topology_receiver = state.topology.subscribe();
 
-    let join_handle = tokio::spawn(monitor_nodes_task(app, interval_seconds));
-    state.monitor_nodes_task = Some(join_handle);
+    state.supervisor.start(MONITOR_NODES_TASK, move || {
+        monitor_nodes_task(app.clone(), topology_receiver.clone(), interval_seconds)
+    });
 
     Ok(())
 }
 
-async fn monitor_nodes_task(app: AppHandle, interval_seconds: u64) {
-    let docker = Docker::connect_with_local_defaults().unwrap();
+async fn monitor_nodes_task(
+    app: AppHandle,
+    topology_receiver: watch::Receiver<Topology>,
+    interval_seconds: u64,
+) -> Result<(), DemoError> {
+    let docker = Docker::connect_with_local_defaults()
+        .map_err(|error| DemoError::Connection(format!("Could not connect to Docker: {error}")))?;
 
     loop {
-        let data_usage = docker.df(None).await.unwrap();
-        let volumes = data_usage.volumes.unwrap();
+        let edge_volume_names = topology_receiver.borrow().edge_volume_names();
+
+        let data_usage = docker.df(None).await.map_err(|error| {
+            DemoError::Docker(format!("Could not read the disk usage: {error}"))
+        })?;
 
-        for volume in volumes {
+        for volume in data_usage.volumes.unwrap_or_default() {
             if volume.name.starts_with("modelardb-cluster") {
-                let size = volume.usage_data.unwrap().size;
                 let node_name = volume.name.replace("modelardb-cluster_", "");
 
-                app.emit(&format!("{node_name}-node-size"), size).unwrap();
+                // Only monitor the nodes that are currently part of the cluster.
+                if edge_volume_names.contains(&node_name) {
+                    if let Some(usage_data) = volume.usage_data {
+                        app.emit_event(&format!("{node_name}-node-size"), usage_data.size);
+                    }
+                }
             }
         }
         time::sleep(Duration::from_secs(interval_seconds)).await;
@@ -497,15 +1451,21 @@ struct ColumnResponse {
 }
 
 #[tauri::command]
-async fn client_tables(url: String) -> Vec<ClientTableResponse> {
+async fn client_tables(url: String) -> Result<Vec<ClientTableResponse>, DemoError> {
     let node = Node::Server(url);
-    let mut client = Client::connect(node).await.unwrap();
+    let mut client = connect_to_modelardb(node).await?;
 
-    let table_names = client.tables().await.unwrap();
+    let table_names = client
+        .tables()
+        .await
+        .map_err(|error| DemoError::FlightStatus(error.to_string()))?;
 
     let mut tables = vec![];
     for table_name in table_names {
-        let schema = client.schema(&table_name).await.unwrap();
+        let schema = client
+            .schema(&table_name)
+            .await
+            .map_err(|error| DemoError::Schema(error.to_string()))?;
 
         let mut columns = vec![];
         for field in schema.fields() {
@@ -521,40 +1481,267 @@ async fn client_tables(url: String) -> Vec<ClientTableResponse> {
         });
     }
 
-    tables
+    Ok(tables)
 }
 
 #[tauri::command]
-async fn client_query(url: String, query: String) -> Vec<u8> {
+async fn add_edge_node(
+    state: State<'_, Mutex<AppState>>,
+    modelardb_url: String,
+    comparison_url: String,
+) -> Result<(), DemoError> {
+    let state = state.lock().await;
+
+    // The running tasks are notified of the change through the topology channel.
+    let mut result = Ok(());
+    state.topology.send_if_modified(|topology| {
+        result = topology.add_edge_node(modelardb_url, comparison_url);
+        result.is_ok()
+    });
+
+    result.map_err(DemoError::Configuration)
+}
+
+#[tauri::command]
+async fn remove_edge_node(
+    state: State<'_, Mutex<AppState>>,
+    modelardb_url: String,
+) -> Result<(), DemoError> {
+    let state = state.lock().await;
+
+    let mut result = Ok(());
+    state.topology.send_if_modified(|topology| {
+        result = topology.remove_edge_node(&modelardb_url);
+        result.is_ok()
+    });
+
+    result.map_err(DemoError::Configuration)
+}
+
+#[tauri::command]
+async fn list_tasks(state: State<'_, Mutex<AppState>>) -> Result<Vec<TaskInfo>, DemoError> {
+    Ok(state.lock().await.supervisor.list())
+}
+
+#[tauri::command]
+async fn pause_task(state: State<'_, Mutex<AppState>>, name: String) -> Result<(), DemoError> {
+    state.lock().await.supervisor.pause(&name)
+}
+
+#[tauri::command]
+async fn resume_task(state: State<'_, Mutex<AppState>>, name: String) -> Result<(), DemoError> {
+    state.lock().await.supervisor.resume(&name)
+}
+
+#[tauri::command]
+async fn set_restart_policy(
+    state: State<'_, Mutex<AppState>>,
+    name: String,
+    restart_policy: RestartPolicy,
+) -> Result<(), DemoError> {
+    state
+        .lock()
+        .await
+        .supervisor
+        .set_restart_policy(&name, restart_policy)
+}
+
+/// Replace the fault schedule with `faults`, which start relative to now, and start pausing and
+/// unpausing the containers of the nodes as scheduled. The containers paused by the previous
+/// schedule that `faults` does not pause are unpaused before the command returns.
+#[tauri::command]
+async fn set_fault_schedule(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    faults: Vec<Fault>,
+) -> Result<(), DemoError> {
+    // The state is not locked while the containers are paused, so other commands are not blocked.
+    let (supervisor, topology_receiver, fault_injector) = {
+        let state = state.lock().await;
+
+        // Stop the current task first, so only this command pauses and unpauses the containers now.
+        state.supervisor.pause_all(&[FAULT_INJECTION_TASK]);
+        state.fault_injector.set_faults(faults);
+
+        (
+            state.supervisor.clone(),
+            state.topology.subscribe(),
+            state.fault_injector.clone(),
+        )
+    };
+
+    let topology = topology_receiver.borrow().clone();
+    faults::pause_containers(&app, &topology, &fault_injector).await;
+
+    supervisor.start(FAULT_INJECTION_TASK, move || {
+        faults::inject_container_faults_task(
+            app.clone(),
+            topology_receiver.clone(),
+            fault_injector.clone(),
+        )
+        .map(Ok)
+    });
+
+    Ok(())
+}
+
+/// Return the requests to the nodes that were affected by a fault since the fault schedule was set.
+#[tauri::command]
+async fn affected_requests(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<AffectedRequest>, DemoError> {
+    Ok(state.lock().await.fault_injector.affected_requests())
+}
+
+/// Compare the rows written to the edge nodes since the ingestion was started to the rows stored by
+/// ModelarDB and the comparison format after they have been flushed.
+#[tauri::command]
+async fn verification_report(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<VerificationReport, DemoError> {
+    // The state is not locked while the nodes are queried, so the other commands are not blocked.
+    let (cloud_url, table_schema, written_rows, comparison_remote_object_store) = {
+        let state = state.lock().await;
+
+        let cloud_url = state
+            .topology
+            .borrow()
+            .cloud_urls
+            .first()
+            .cloned()
+            .ok_or_else(|| {
+                DemoError::Configuration(
+                    "There is no cloud node to verify the rows with.".to_owned(),
+                )
+            })?;
+
+        let written_rows = state.written_rows.lock().unwrap().clone();
+
+        (
+            cloud_url,
+            state.data_source.table_schema(),
+            written_rows,
+            state.comparison_remote_object_store.clone(),
+        )
+    };
+
+    verification::try_verify(
+        written_rows,
+        TABLE_NAME,
+        table_schema.field(0).name(),
+        &cloud_url,
+        comparison_remote_object_store,
+    )
+    .await
+}
+
+/// Start recording the batches written to the nodes, the flushes, and the emitted events into the
+/// session directory at `path`.
+#[tauri::command]
+async fn start_session_recording(
+    session_recorder: State<'_, Arc<SessionRecorder>>,
+    path: String,
+) -> Result<(), DemoError> {
+    session_recorder.start(Path::new(&path))
+}
+
+#[tauri::command]
+async fn stop_session_recording(
+    session_recorder: State<'_, Arc<SessionRecorder>>,
+) -> Result<(), DemoError> {
+    session_recorder.stop()
+}
+
+/// Replay the session recorded in the directory at `path` at `speed`, which defaults to the pace it
+/// was recorded at. The pipeline tasks are stopped first, so only the recorded requests are sent to
+/// the nodes.
+#[tauri::command]
+async fn replay_session(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    path: String,
+    speed: Option<ReplaySpeed>,
+) -> Result<(), DemoError> {
+    let state = state.lock().await;
+    state.abort_tasks();
+
+    let path = PathBuf::from(path);
+    let speed = speed.unwrap_or(ReplaySpeed::Realtime);
+    let modelardb_remote_object_store = state.modelardb_remote_object_store.clone();
+    let comparison_remote_object_store = state.comparison_remote_object_store.clone();
+
+    // Restarting the replay would send the recorded requests again from the beginning.
+    state
+        .supervisor
+        .start_with_policy(REPLAY_SESSION_TASK, RestartPolicy::Never, move || {
+            session::replay_session(
+                app.clone(),
+                path.clone(),
+                speed,
+                modelardb_remote_object_store.clone(),
+                comparison_remote_object_store.clone(),
+            )
+        });
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn node_health(state: State<'_, Mutex<AppState>>) -> Result<HealthMap, DemoError> {
+    Ok(state.lock().await.node_health.borrow().clone())
+}
+
+#[tauri::command]
+async fn cluster_topology(state: State<'_, Mutex<AppState>>) -> Result<ClusterTopology, DemoError> {
+    Ok(state.lock().await.topology.borrow().cluster_topology())
+}
+
+#[tauri::command]
+async fn cluster_nodes(state: State<'_, Mutex<AppState>>) -> Result<Vec<NodeConfig>, DemoError> {
+    Ok(state.lock().await.topology.borrow().nodes.clone())
+}
+
+#[tauri::command]
+async fn client_query(
+    state: State<'_, Mutex<AppState>>,
+    url: String,
+    query: String,
+) -> Result<Vec<u8>, DemoError> {
+    let is_cloud_node = state.lock().await.topology.borrow().is_cloud_url(&url);
+
     let node = Node::Server(url.clone());
-    let mut client = Client::connect(node.clone()).await.unwrap();
+    let mut client = connect_to_modelardb(node.clone()).await?;
 
     // If it is not a cloud node, flush the memory of the edge node before querying.
-    if url != *"grpc://127.0.0.1:9999" && url != *"grpc://127.0.0.1:9899" {
-        let mut flight_client = FlightServiceClient::connect(node.url().to_owned())
-            .await
-            .unwrap();
+    if !is_cloud_node {
+        let mut flight_client = FlightServiceClient::connect(node.url().to_owned()).await?;
 
         let action = Action {
             r#type: "FlushMemory".to_owned(),
             body: vec![].into(),
         };
 
-        flight_client.do_action(action).await.unwrap();
+        flight_client.do_action(action).await?;
     }
 
-    let record_batch_stream = client.read(&query).await.unwrap();
-    let record_batches = common::collect(record_batch_stream).await.unwrap();
+    let record_batch_stream = client
+        .read(&query)
+        .await
+        .map_err(|error| DemoError::FlightStatus(error.to_string()))?;
+
+    let record_batches = common::collect(record_batch_stream)
+        .await
+        .map_err(|error| DemoError::FlightStatus(error.to_string()))?;
     let record_batch_slice: Vec<&RecordBatch> = record_batches.iter().collect();
 
     // Write the record batch out as a JSON array.
     let buf = Vec::new();
 
     let mut writer = ArrayWriter::new(buf);
-    writer.write_batches(&record_batch_slice).unwrap();
-    writer.finish().unwrap();
+    writer.write_batches(&record_batch_slice)?;
+    writer.finish()?;
 
-    writer.into_inner()
+    Ok(writer.into_inner())
 }
 
 #[derive(Deserialize)]
@@ -565,15 +1752,15 @@ struct ScriptRunResponse {
 }
 
 #[tauri::command]
-async fn run_python_script(filename: String) -> (Vec<u8>, Vec<u8>, i32) {
+async fn run_python_script(filename: String) -> Result<(Vec<u8>, Vec<u8>, i32), DemoError> {
     let resp = reqwest::get(format!("http://127.0.0.1:8000/run/?script={filename}"))
         .await
-        .unwrap()
+        .map_err(|error| DemoError::ScriptRunner(error.to_string()))?
         .json::<ScriptRunResponse>()
         .await
-        .unwrap();
+        .map_err(|error| DemoError::ScriptRunner(error.to_string()))?;
 
-    (resp.output.into_bytes(), resp.error.into_bytes(), resp.code)
+    Ok((resp.output.into_bytes(), resp.error.into_bytes(), resp.code))
 }
 
 #[derive(Deserialize)]
@@ -582,15 +1769,20 @@ struct ScriptReadResponse {
 }
 
 #[tauri::command]
-async fn read_python_script(filename: String) -> String {
+async fn read_python_script(filename: String) -> Result<String, DemoError> {
     let resp = reqwest::get(format!("http://127.0.0.1:8000/read/?script={filename}"))
         .await
-        .unwrap()
+        .map_err(|error| DemoError::ScriptRunner(error.to_string()))?
         .json::<ScriptReadResponse>()
         .await
-        .unwrap();
+        .map_err(|error| DemoError::ScriptRunner(error.to_string()))?;
 
-    resp.script
+    Ok(resp.script)
+}
+
+#[tauri::command]
+async fn failed_emits(failed_emits: State<'_, FailedEmits>) -> Result<u64, DemoError> {
+    Ok(failed_emits.count())
 }
 
 #[tauri::command]
@@ -598,25 +1790,105 @@ async fn google_maps_api_key() -> String {
     std::env::var("GOOGLE_MAPS_API_KEY").unwrap_or("".to_owned())
 }
 
+/// Run the demo pipeline without the Tauri app using the command line arguments `args`, excluding
+/// the program name.
+pub fn run_cli(args: Vec<String>) -> Result<(), String> {
+    match headless::CliOptions::try_from_args(args)? {
+        Some(options) => tauri::async_runtime::block_on(headless::run(options))
+            .map_err(|error| error.to_string()),
+        None => Ok(()),
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
         .setup(|app| {
-            app.manage(Mutex::new(AppState::new()));
+            let nodes_file_path = match std::env::var(NODES_FILE_ENV) {
+                Ok(path) => path.into(),
+                Err(_) => app
+                    .path()
+                    .resolve("resources/nodes.json", BaseDirectory::Resource)?,
+            };
+
+            let topology = Topology::try_from_file(&nodes_file_path)?;
+            let storage_config = StorageConfig::try_from_env()?;
+
+            let dataset_path = app
+                .path()
+                .resolve("resources/wind_cleaned.parquet", BaseDirectory::Resource)?;
+            let data_source = DataSource::File(Dataset::try_new_wind(dataset_path)?);
+
+            let state =
+                AppState::try_new(app.handle().clone(), topology, data_source, &storage_config)?;
+
+            // Keep the topology up to date with the nodes registered with the ModelarDB manager.
+            let app_handle = app.handle().clone();
+            let topology_sender = state.topology.clone();
+            state.supervisor.start(DISCOVERY_TASK, move || {
+                discovery::discover_nodes_task(app_handle.clone(), topology_sender.clone()).map(Ok)
+            });
+
+            tauri::async_runtime::spawn(discovery::emit_cluster_topology_task(
+                app.handle().clone(),
+                state.topology.subscribe(),
+            ));
+
+            let app_handle = app.handle().clone();
+            let topology_receiver = state.topology.subscribe();
+            let health_sender = state.node_health.clone();
+            state.supervisor.start(HEALTH_TASK, move || {
+                health::monitor_health_task(
+                    app_handle.clone(),
+                    topology_receiver.clone(),
+                    health_sender.clone(),
+                )
+                .map(Ok)
+            });
+
+            app.manage(Mutex::new(state));
+            app.manage(Arc::new(SessionRecorder::default()));
+            app.manage(FailedEmits::default());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
+            start_cluster,
+            stop_cluster,
+            restart_node,
+            pause_demo,
+            resume_demo,
             reset_state,
+            set_dataset,
+            set_synthetic_dataset,
+            set_turbine_layout,
             create_table,
+            propose_column_mapping,
+            create_table_from_dataset,
             ingest_into_table,
             flush_nodes,
             monitor_nodes,
+            add_edge_node,
+            remove_edge_node,
+            cluster_topology,
+            cluster_nodes,
+            node_health,
+            list_tasks,
+            pause_task,
+            resume_task,
+            set_restart_policy,
+            set_fault_schedule,
+            affected_requests,
+            verification_report,
+            start_session_recording,
+            stop_session_recording,
+            replay_session,
             client_tables,
             client_query,
             run_python_script,
             read_python_script,
+            failed_emits,
             google_maps_api_key
         ])
         .run(tauri::generate_context!())
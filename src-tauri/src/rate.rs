@@ -0,0 +1,283 @@
+use std::f64::consts::PI;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use modelardb_types::types::{TimestampArray, TimestampBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::replay::Replay;
+use crate::util::Random;
+
+/// How often rows are ingested into the edge nodes.
+pub(super) const INGESTION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How the number of rows ingested into each edge node per second changes over time.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum RateProfile {
+    /// The same number of rows every second.
+    Constant { rows_per_second: f64 },
+    /// Increase linearly from `start_rows_per_second` to `end_rows_per_second` over
+    /// `duration_seconds` and stay at `end_rows_per_second` after that.
+    Ramp {
+        start_rows_per_second: f64,
+        end_rows_per_second: f64,
+        duration_seconds: u64,
+    },
+    /// Alternate between `min_rows_per_second` and `max_rows_per_second` like the load of a turbine
+    /// over a day and a night, starting at the minimum.
+    Sinusoidal {
+        min_rows_per_second: f64,
+        max_rows_per_second: f64,
+        period_seconds: u64,
+    },
+    /// Ingest `rows_per_second` rows, except for bursts of `burst_rows_per_second` rows that last
+    /// `burst_seconds` and start each second with the probability `burst_probability`.
+    Bursts {
+        rows_per_second: f64,
+        burst_rows_per_second: f64,
+        burst_probability: f64,
+        burst_seconds: u64,
+        /// Seed for the bursts so a run can be repeated. Defaults to the current time.
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+}
+
+/// The rate profile of the ingestion, how the rate differs between the edge nodes, and how far
+/// apart the timestamps of the ingested rows are.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(super) struct IngestionRate {
+    pub(super) profile: RateProfile,
+    /// The rate of the edge node at each position is multiplied by the multiplier at the same
+    /// position. Edge nodes without a multiplier use 1.
+    #[serde(default)]
+    pub(super) node_multipliers: Vec<f64>,
+    /// The time between the timestamps of consecutive rows from an edge node. Defaults to spreading
+    /// the rows ingested each second evenly over that second.
+    #[serde(default)]
+    pub(super) sampling_interval_ms: Option<u64>,
+    /// Replay the data set using its own timestamps instead. The rate profile then only limits the
+    /// number of rows per second when replaying as fast as possible.
+    #[serde(default)]
+    pub(super) replay: Option<Replay>,
+}
+
+impl IngestionRate {
+    /// Ingest `rows_per_second` rows into each edge node every second.
+    pub(super) fn constant(rows_per_second: usize) -> Self {
+        Self {
+            profile: RateProfile::Constant {
+                rows_per_second: rows_per_second as f64,
+            },
+            node_multipliers: vec![],
+            sampling_interval_ms: None,
+            replay: None,
+        }
+    }
+}
+
+/// Computes the number of rows to ingest into each edge node at each tick of the ingestion.
+pub(super) struct RateGenerator {
+    rate: IngestionRate,
+    ticks: u64,
+    burst_ticks_left: u64,
+    random: Random,
+}
+
+impl RateGenerator {
+    pub(super) fn new(rate: IngestionRate) -> Self {
+        let seed = match rate.profile {
+            RateProfile::Bursts { seed, .. } => seed,
+            _ => None,
+        };
+
+        Self {
+            rate,
+            ticks: 0,
+            burst_ticks_left: 0,
+            random: Random::from_seed(seed),
+        }
+    }
+
+    /// How the data set is replayed, if it is replayed.
+    pub(super) fn replay(&self) -> Option<Replay> {
+        self.rate.replay
+    }
+
+    /// Advance to the next tick and return the number of rows to ingest into each of the
+    /// `num_nodes` edge nodes during it.
+    pub(super) fn next_tick(&mut self, num_nodes: usize) -> Vec<usize> {
+        let elapsed = (self.ticks * INGESTION_INTERVAL.as_secs()) as f64;
+        self.ticks += 1;
+
+        let rows_per_second = match self.rate.profile {
+            RateProfile::Constant { rows_per_second } => rows_per_second,
+            RateProfile::Ramp {
+                start_rows_per_second,
+                end_rows_per_second,
+                duration_seconds,
+            } => {
+                let progress = if duration_seconds > 0 {
+                    (elapsed / duration_seconds as f64).min(1.0)
+                } else {
+                    1.0
+                };
+
+                start_rows_per_second + (end_rows_per_second - start_rows_per_second) * progress
+            }
+            RateProfile::Sinusoidal {
+                min_rows_per_second,
+                max_rows_per_second,
+                period_seconds,
+            } => {
+                let phase = 2.0 * PI * elapsed / period_seconds.max(1) as f64;
+                let load = (1.0 - phase.cos()) / 2.0;
+
+                min_rows_per_second + (max_rows_per_second - min_rows_per_second) * load
+            }
+            RateProfile::Bursts {
+                rows_per_second,
+                burst_rows_per_second,
+                burst_probability,
+                burst_seconds,
+                ..
+            } => {
+                if self.burst_ticks_left == 0 && self.random.next_f64() < burst_probability {
+                    self.burst_ticks_left = burst_seconds / INGESTION_INTERVAL.as_secs();
+                }
+
+                if self.burst_ticks_left > 0 {
+                    self.burst_ticks_left -= 1;
+                    burst_rows_per_second
+                } else {
+                    rows_per_second
+                }
+            }
+        };
+
+        let rows_per_tick = rows_per_second * INGESTION_INTERVAL.as_secs_f64();
+
+        (0..num_nodes)
+            .map(|node_index| {
+                let multiplier = self
+                    .rate
+                    .node_multipliers
+                    .get(node_index)
+                    .copied()
+                    .unwrap_or(1.0);
+
+                (rows_per_tick * multiplier).max(0.0).round() as usize
+            })
+            .collect()
+    }
+
+    /// Return the timestamps of `num_rows` rows ingested into an edge node during a tick. The
+    /// timestamps continue from `next_timestamp`, the timestamp after the last row ingested into
+    /// the edge node, unless it is in the past, and it is updated to the timestamp after the rows.
+    pub(super) fn timestamps(
+        &self,
+        next_timestamp: &mut Option<i64>,
+        num_rows: usize,
+    ) -> TimestampArray {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_micros() as i64;
+
+        let step = match self.rate.sampling_interval_ms {
+            Some(sampling_interval_ms) => sampling_interval_ms as i64 * 1000,
+            None => INGESTION_INTERVAL.as_micros() as i64 / num_rows.max(1) as i64,
+        };
+
+        let mut timestamp = next_timestamp.unwrap_or(now).max(now);
+        let mut timestamps = TimestampBuilder::with_capacity(num_rows);
+
+        for _ in 0..num_rows {
+            timestamps.append_value(timestamp);
+            timestamp += step;
+        }
+
+        *next_timestamp = Some(timestamp);
+        timestamps.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(profile: RateProfile) -> IngestionRate {
+        IngestionRate {
+            profile,
+            ..IngestionRate::constant(0)
+        }
+    }
+
+    #[test]
+    fn test_next_tick_constant_with_node_multipliers() {
+        let mut rate = IngestionRate::constant(100);
+        rate.node_multipliers = vec![0.5, 2.0];
+        let mut rate_generator = RateGenerator::new(rate);
+
+        assert_eq!(rate_generator.next_tick(3), [50, 200, 100]);
+        assert_eq!(rate_generator.next_tick(3), [50, 200, 100]);
+    }
+
+    #[test]
+    fn test_next_tick_ramp_stays_at_end() {
+        let mut rate_generator = RateGenerator::new(rate(RateProfile::Ramp {
+            start_rows_per_second: 100.0,
+            end_rows_per_second: 200.0,
+            duration_seconds: 4,
+        }));
+
+        let counts: Vec<usize> = (0..6).map(|_| rate_generator.next_tick(1)[0]).collect();
+        assert_eq!(counts, [100, 125, 150, 175, 200, 200]);
+    }
+
+    #[test]
+    fn test_next_tick_sinusoidal_starts_at_minimum() {
+        let mut rate_generator = RateGenerator::new(rate(RateProfile::Sinusoidal {
+            min_rows_per_second: 100.0,
+            max_rows_per_second: 300.0,
+            period_seconds: 4,
+        }));
+
+        let counts: Vec<usize> = (0..5).map(|_| rate_generator.next_tick(1)[0]).collect();
+        assert_eq!(counts, [100, 200, 300, 200, 100]);
+    }
+
+    #[test]
+    fn test_next_tick_bursts_last_burst_seconds() {
+        let mut rate_generator = RateGenerator::new(rate(RateProfile::Bursts {
+            rows_per_second: 100.0,
+            burst_rows_per_second: 1000.0,
+            burst_probability: 1.0,
+            burst_seconds: 3,
+            seed: Some(1),
+        }));
+
+        let counts: Vec<usize> = (0..3).map(|_| rate_generator.next_tick(1)[0]).collect();
+        assert_eq!(counts, [1000, 1000, 1000]);
+    }
+
+    #[test]
+    fn test_next_tick_without_bursts() {
+        let mut rate_generator = RateGenerator::new(rate(RateProfile::Bursts {
+            rows_per_second: 100.0,
+            burst_rows_per_second: 1000.0,
+            burst_probability: 0.0,
+            burst_seconds: 3,
+            seed: Some(1),
+        }));
+
+        let counts: Vec<usize> = (0..3).map(|_| rate_generator.next_tick(2)[1]).collect();
+        assert_eq!(counts, [100, 100, 100]);
+    }
+
+    #[test]
+    fn test_next_tick_without_edge_nodes() {
+        let mut rate_generator = RateGenerator::new(IngestionRate::constant(100));
+        assert!(rate_generator.next_tick(0).is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use arrow::array::RecordBatch;
+use modelardb_types::types::{ArrowTimestamp, TimestampArray};
+use serde::{Deserialize, Serialize};
+
+use crate::dataset::PartitionReader;
+use crate::error::DemoError;
+use crate::rate::INGESTION_INTERVAL;
+
+/// How fast the data set is replayed compared to the time between its timestamps.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum ReplaySpeed {
+    /// Ingest the rows at the same pace as their timestamps.
+    Realtime,
+    /// Ingest the rows `factor` times faster than their timestamps, e.g., an hour per minute for 60.
+    Accelerated { factor: f64 },
+    /// Ingest the rows as fast as the nodes accept them, limited by the ingestion rate.
+    AsFastAsPossible,
+}
+
+/// Replay the data set using the timestamps in its timestamp column instead of generating them.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub(super) struct Replay {
+    pub(super) speed: ReplaySpeed,
+    /// Shift the timestamps so the first row replayed into each edge node lands at the current time.
+    #[serde(default)]
+    pub(super) shift_to_now: bool,
+}
+
+impl Replay {
+    /// If the ingestion should continue with the next rows as soon as the current rows have been
+    /// ingested instead of waiting for the next second.
+    pub(super) fn is_unpaced(&self) -> bool {
+        matches!(self.speed, ReplaySpeed::AsFastAsPossible)
+    }
+
+    /// Read the rows to replay into an edge node during this second from `partition_reader`. The
+    /// replay of the edge node starts over from `clock` being [`None`] when the end of its partition
+    /// is reached. At most `max_rows` rows are read when replaying as fast as possible.
+    pub(super) async fn try_next(
+        &self,
+        partition_reader: &mut PartitionReader,
+        clock: &mut Option<ReplayClock>,
+        max_rows: usize,
+    ) -> Result<RecordBatch, DemoError> {
+        if partition_reader.is_at_end() {
+            if partition_reader.is_finished() {
+                return Ok(RecordBatch::new_empty(partition_reader.schema()));
+            }
+
+            partition_reader.rewind();
+            *clock = None;
+        }
+
+        if clock.is_none() {
+            let Some(source_start) = partition_reader.try_peek_timestamp().await? else {
+                return Ok(RecordBatch::new_empty(partition_reader.schema()));
+            };
+
+            let shift = if self.shift_to_now {
+                now_micros() - source_start
+            } else {
+                0
+            };
+
+            *clock = Some(ReplayClock {
+                source_start,
+                wall_start: Instant::now(),
+                shift,
+            });
+        }
+
+        let clock = clock.as_ref().unwrap();
+
+        let (max_rows, end_timestamp) = match self.speed {
+            ReplaySpeed::Realtime => (usize::MAX, clock.source_end(1.0)),
+            ReplaySpeed::Accelerated { factor } => (usize::MAX, clock.source_end(factor)),
+            ReplaySpeed::AsFastAsPossible => (max_rows, i64::MAX),
+        };
+
+        partition_reader
+            .try_next_before(max_rows, end_timestamp)
+            .await
+    }
+}
+
+/// When the replay into an edge node started, both in the data set and in wall-clock time.
+pub(super) struct ReplayClock {
+    source_start: i64,
+    wall_start: Instant,
+    /// Microseconds added to the timestamps in the data set.
+    shift: i64,
+}
+
+impl ReplayClock {
+    /// Return `timestamps` from the data set shifted as requested by the replay.
+    pub(super) fn shift_timestamps(&self, timestamps: &TimestampArray) -> TimestampArray {
+        let shift = self.shift;
+        timestamps.unary::<_, ArrowTimestamp>(|timestamp| timestamp + shift)
+    }
+
+    /// Return the timestamp in the data set that the replay has reached at the end of the current
+    /// second when it is `factor` times faster than the timestamps.
+    fn source_end(&self, factor: f64) -> i64 {
+        let elapsed = self.wall_start.elapsed() + INGESTION_INTERVAL;
+        self.source_start + (elapsed.as_micros() as f64 * factor) as i64
+    }
+}
+
+fn now_micros() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_micros() as i64
+}
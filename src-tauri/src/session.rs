@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use arrow::array::RecordBatch;
+use arrow_flight::flight_service_client::FlightServiceClient;
+use arrow_flight::{Action, Ticket};
+use modelardb_embedded::operations::client::{Client, Node};
+use modelardb_embedded::operations::Operations;
+use modelardb_embedded::TableType;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::time;
+use tonic::transport::Channel;
+
+use crate::error::DemoError;
+use crate::error_bounds::{self, FieldErrorBound};
+use crate::events::EventEmitter;
+use crate::ingested_size::IngestedSize;
+use crate::replay::ReplaySpeed;
+use crate::util;
+
+/// The file in a session directory with an entry for each request and event as a line of JSON.
+const LOG_FILE: &str = "session.jsonl";
+
+/// The file in a session directory with the recorded batches, each stored as an Arrow IPC stream.
+const BATCHES_FILE: &str = "batches.ipc";
+
+/// Where a recorded batch is stored in the batches file of a session.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub(super) struct BatchLocation {
+    offset: u64,
+    length: u64,
+}
+
+/// The system a node that is flushed belongs to.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum System {
+    Modelardb,
+    Comparison,
+}
+
+/// A request sent to the cluster or an event emitted while a session is recorded.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum SessionEntry {
+    /// The time series table with `table_name` was created in the cluster managed by the manager
+    /// with `url`. Its schema is stored as an empty batch and its field columns have
+    /// `error_bounds`.
+    CreateTable {
+        url: String,
+        table_name: String,
+        schema: BatchLocation,
+        error_bounds: Vec<FieldErrorBound>,
+    },
+    /// The batch was written to the table with `table_name` on the ModelarDB node with `url`.
+    ModelardbWrite {
+        url: String,
+        table_name: String,
+        batch: BatchLocation,
+    },
+    /// The batch was sent to the comparison node with `url` with the action `action`.
+    ComparisonWrite {
+        url: String,
+        action: String,
+        batch: BatchLocation,
+    },
+    /// The node with `url` was flushed with the action `action`. The comparison nodes are told
+    /// which table the files are flushed to with `table_name`.
+    Flush {
+        url: String,
+        system: System,
+        action: String,
+        #[serde(default)]
+        table_name: Option<String>,
+    },
+    /// The ModelarDB node with `url` was vacuumed.
+    Vacuum { url: String },
+    /// The event `event` was emitted with `payload`.
+    Event { event: String, payload: Value },
+}
+
+/// An entry in the log of a session and when it was recorded.
+#[derive(Serialize, Deserialize)]
+struct LogLine {
+    /// Milliseconds since the recording was started.
+    elapsed_ms: u64,
+    #[serde(flatten)]
+    entry: SessionEntry,
+}
+
+struct Session {
+    start: Instant,
+    log: BufWriter<File>,
+    batches: BufWriter<File>,
+    /// The size of the batches file, which is where the next batch is stored.
+    batches_size: u64,
+}
+
+struct RecorderState {
+    session: Option<Session>,
+    /// The error the current recording failed with, which stops it.
+    error: Option<DemoError>,
+}
+
+/// Records the batches written to the cluster, the flushes, and the emitted events into a session
+/// directory while a recording is active, so the session can be replayed later.
+pub(super) struct SessionRecorder {
+    state: Mutex<RecorderState>,
+}
+
+impl Default for SessionRecorder {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(RecorderState {
+                session: None,
+                error: None,
+            }),
+        }
+    }
+}
+
+impl SessionRecorder {
+    /// Start recording into the directory at `path`, which is created if it does not exist. A
+    /// recording that is already active is stopped first.
+    pub(super) fn start(&self, path: &Path) -> Result<(), DemoError> {
+        self.stop()?;
+
+        fs::create_dir_all(path).map_err(|error| io_error(path, error))?;
+        let create = |name: &str| {
+            let file_path = path.join(name);
+            File::create(&file_path)
+                .map(BufWriter::new)
+                .map_err(|error| io_error(&file_path, error))
+        };
+
+        let session = Session {
+            start: Instant::now(),
+            log: create(LOG_FILE)?,
+            batches: create(BATCHES_FILE)?,
+            batches_size: 0,
+        };
+
+        self.state.lock().unwrap().session = Some(session);
+
+        Ok(())
+    }
+
+    /// Stop the recording, if one is active, and write what has not been written yet. Returns the
+    /// error the recording failed with, if it failed.
+    pub(super) fn stop(&self) -> Result<(), DemoError> {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(mut session) = state.session.take() {
+            if let Err(error) = session.log.flush().and(session.batches.flush()) {
+                return Err(DemoError::Configuration(format!(
+                    "Could not write the session: {error}"
+                )));
+            }
+        }
+
+        match state.error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Store `record_batch` in the batches file and return where it is stored, or [`None`] if no
+    /// recording is active.
+    pub(super) fn record_batch(&self, record_batch: &RecordBatch) -> Option<BatchLocation> {
+        self.with_session(|session| {
+            let bytes = util::try_convert_record_batch_to_bytes(record_batch)?;
+            session
+                .batches
+                .write_all(&bytes)
+                .map_err(|error| io_error(Path::new(BATCHES_FILE), error))?;
+
+            let batch = BatchLocation {
+                offset: session.batches_size,
+                length: bytes.len() as u64,
+            };
+            session.batches_size += batch.length;
+
+            Ok(batch)
+        })
+    }
+
+    /// Add `entry` to the log if a recording is active.
+    pub(super) fn record(&self, entry: SessionEntry) {
+        self.with_session(|session| {
+            let log_line = LogLine {
+                elapsed_ms: session.start.elapsed().as_millis() as u64,
+                entry,
+            };
+
+            let line = serde_json::to_string(&log_line)
+                .map_err(|error| DemoError::Schema(error.to_string()))?;
+            writeln!(session.log, "{line}").map_err(|error| io_error(Path::new(LOG_FILE), error))
+        });
+    }
+
+    /// Add the `event` with `payload` to the log if a recording is active.
+    pub(super) fn record_event<S: Serialize>(&self, event: &str, payload: &S) {
+        // The payload is only serialized while recording, as events are emitted often.
+        if self.state.lock().unwrap().session.is_some() {
+            if let Ok(payload) = serde_json::to_value(payload) {
+                self.record(SessionEntry::Event {
+                    event: event.to_owned(),
+                    payload,
+                });
+            }
+        }
+    }
+
+    /// Run `record` with the active session. If it fails, the recording is stopped and the error is
+    /// kept until the recording is stopped.
+    fn with_session<T>(
+        &self,
+        record: impl FnOnce(&mut Session) -> Result<T, DemoError>,
+    ) -> Option<T> {
+        let mut state = self.state.lock().unwrap();
+        let session = state.session.as_mut()?;
+
+        match record(session) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                state.session = None;
+                state.error = Some(error);
+                None
+            }
+        }
+    }
+}
+
+/// Record `entry` with the session recorder of `emitter`, if it has one and it is recording.
+pub(super) fn record<E: EventEmitter>(emitter: &E, entry: SessionEntry) {
+    if let Some(session_recorder) = emitter.session_recorder() {
+        session_recorder.record(entry);
+    }
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> DemoError {
+    DemoError::Configuration(format!("Could not write {}: {error}", path.display()))
+}
+
+#[derive(Clone, Serialize)]
+struct SessionReplayed {
+    entries: usize,
+    batches: usize,
+    duration_ms: u128,
+}
+
+/// Send the table creation, writes, flushes, and vacuums recorded in the session directory at
+/// `path` to the nodes in the same order and at the recorded pace adjusted to `speed`. A table
+/// that already exists is replaced when the table creation is replayed. The events are not
+/// replayed, instead the error bounds of the created table, the ingested size, and the size of the
+/// tables after each flush are emitted as they are measured now, followed by a `session-replayed`
+/// event.
+pub(super) async fn replay_session<E: EventEmitter>(
+    emitter: E,
+    path: PathBuf,
+    speed: ReplaySpeed,
+    modelardb_remote_object_store: Arc<dyn ObjectStore>,
+    comparison_remote_object_store: Arc<dyn ObjectStore>,
+) -> Result<(), DemoError> {
+    let read_error = |file_path: &Path, error: std::io::Error| {
+        DemoError::Configuration(format!("Could not read {}: {error}", file_path.display()))
+    };
+
+    let log_path = path.join(LOG_FILE);
+    let log = File::open(&log_path).map_err(|error| read_error(&log_path, error))?;
+
+    let batches_path = path.join(BATCHES_FILE);
+    let mut batches =
+        File::open(&batches_path).map_err(|error| read_error(&batches_path, error))?;
+
+    let mut read_batch = |batch: BatchLocation| -> Result<Vec<u8>, DemoError> {
+        let mut bytes = vec![0; batch.length as usize];
+        batches
+            .seek(SeekFrom::Start(batch.offset))
+            .and_then(|_| batches.read_exact(&mut bytes))
+            .map_err(|error| read_error(&batches_path, error))?;
+
+        Ok(bytes)
+    };
+
+    let mut modelardb_clients: HashMap<String, Client> = HashMap::new();
+    let mut flight_clients: HashMap<String, FlightServiceClient<Channel>> = HashMap::new();
+
+    let start = Instant::now();
+    let mut entries = 0;
+    let mut num_batches = 0;
+
+    for line in BufReader::new(log).lines() {
+        let line = line.map_err(|error| read_error(&log_path, error))?;
+        let log_line: LogLine = serde_json::from_str(&line).map_err(|error| {
+            DemoError::Configuration(format!("Could not parse {}: {error}", log_path.display()))
+        })?;
+
+        let elapsed = Duration::from_millis(log_line.elapsed_ms);
+        let due = match speed {
+            ReplaySpeed::Realtime => Some(elapsed),
+            ReplaySpeed::Accelerated { factor } => Some(elapsed.div_f64(factor.max(f64::EPSILON))),
+            ReplaySpeed::AsFastAsPossible => None,
+        };
+
+        if let Some(due) = due {
+            time::sleep(due.saturating_sub(start.elapsed())).await;
+        }
+
+        match log_line.entry {
+            SessionEntry::CreateTable {
+                url,
+                table_name,
+                schema,
+                error_bounds,
+            } => {
+                let schema = util::try_convert_bytes_to_schema(&read_batch(schema)?)?;
+                let modelardb_error_bounds =
+                    error_bounds::try_modelardb_error_bounds(&error_bounds)?;
+
+                let mut client = crate::connect_to_modelardb(Node::Manager(url)).await?;
+                let table_names = client
+                    .tables()
+                    .await
+                    .map_err(|error| DemoError::FlightStatus(error.to_string()))?;
+
+                if table_names.contains(&table_name) {
+                    client
+                        .drop(&table_name)
+                        .await
+                        .map_err(|error| DemoError::FlightStatus(error.to_string()))?;
+                }
+
+                let table_type = TableType::TimeSeriesTable(
+                    schema.as_ref().clone(),
+                    modelardb_error_bounds,
+                    HashMap::new(),
+                );
+
+                client
+                    .create(&table_name, table_type)
+                    .await
+                    .map_err(|error| DemoError::FlightStatus(error.to_string()))?;
+
+                emitter.emit_event("error-bounds", error_bounds);
+            }
+            SessionEntry::ModelardbWrite {
+                url,
+                table_name,
+                batch,
+            } => {
+                let record_batches =
+                    util::try_convert_bytes_to_record_batches(&read_batch(batch)?)?;
+
+                if !modelardb_clients.contains_key(&url) {
+                    let client = Client::connect(Node::Server(url.clone()))
+                        .await
+                        .map_err(|error| DemoError::Connection(error.to_string()))?;
+                    modelardb_clients.insert(url.clone(), client);
+                }
+                let client = modelardb_clients.get_mut(&url).unwrap();
+
+                for record_batch in record_batches {
+                    emitter.emit_event(
+                        "data-ingested",
+                        IngestedSize::try_new(&table_name, &url, &record_batch)?,
+                    );
+
+                    client
+                        .write(&table_name, record_batch)
+                        .await
+                        .map_err(|error| DemoError::FlightStatus(error.to_string()))?;
+                }
+
+                num_batches += 1;
+            }
+            SessionEntry::ComparisonWrite { url, action, batch } => {
+                let action = Action {
+                    r#type: action,
+                    body: read_batch(batch)?.into(),
+                };
+
+                flight_client(&mut flight_clients, &url)
+                    .await?
+                    .do_action(action)
+                    .await?;
+            }
+            SessionEntry::Flush {
+                url,
+                system,
+                action,
+                table_name,
+            } => {
+                let action = Action {
+                    r#type: action,
+                    body: table_name.unwrap_or_default().into_bytes().into(),
+                };
+
+                flight_client(&mut flight_clients, &url)
+                    .await?
+                    .do_action(action)
+                    .await?;
+
+                // The ModelarDB nodes are measured after they are vacuumed.
+                if let System::Comparison = system {
+                    crate::emit_remote_object_store_table_size(
+                        emitter.clone(),
+                        comparison_remote_object_store.clone(),
+                        "comparison".to_owned(),
+                    )
+                    .await?;
+                }
+            }
+            SessionEntry::Vacuum { url } => {
+                flight_client(&mut flight_clients, &url)
+                    .await?
+                    .do_get(Ticket::new("VACUUM".to_owned()))
+                    .await?;
+
+                crate::emit_remote_object_store_table_size(
+                    emitter.clone(),
+                    modelardb_remote_object_store.clone(),
+                    "modelardb".to_owned(),
+                )
+                .await?;
+            }
+            SessionEntry::Event { .. } => (),
+        }
+
+        entries += 1;
+    }
+
+    emitter.emit_event(
+        "session-replayed",
+        SessionReplayed {
+            entries,
+            batches: num_batches,
+            duration_ms: start.elapsed().as_millis(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Return the Apache Arrow Flight client for the node with `url` in `flight_clients`, connecting to
+/// the node if it has not been connected to yet.
+async fn flight_client<'a>(
+    flight_clients: &'a mut HashMap<String, FlightServiceClient<Channel>>,
+    url: &str,
+) -> Result<&'a mut FlightServiceClient<Channel>, DemoError> {
+    if !flight_clients.contains_key(url) {
+        let flight_client = FlightServiceClient::connect(url.to_owned()).await?;
+        flight_clients.insert(url.to_owned(), flight_client);
+    }
+
+    Ok(flight_clients.get_mut(url).unwrap())
+}
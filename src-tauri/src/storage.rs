@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::local::LocalFileSystem;
+use object_store::ObjectStore;
+use serde::Deserialize;
+
+/// Environment variable that can be set to the path of a storage configuration file.
+const STORAGE_FILE_ENV: &str = "MODELARDB_DEMO_STORAGE_FILE";
+
+/// Environment variable that can be set to the backend to use if no storage configuration file is
+/// given. The credentials are then read from the backend's standard environment variables, e.g.,
+/// `AWS_ACCESS_KEY_ID` for S3.
+const STORAGE_BACKEND_ENV: &str = "MODELARDB_DEMO_STORAGE_BACKEND";
+
+/// Environment variable with the root directory of the local backend if no storage configuration
+/// file is given.
+const STORAGE_PATH_ENV: &str = "MODELARDB_DEMO_STORAGE_PATH";
+
+/// The kind of object store the tables of the ModelarDB and comparison nodes are stored in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum StorageBackend {
+    S3,
+    Azure,
+    Gcs,
+    Local,
+}
+
+impl FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "s3" => Ok(Self::S3),
+            "azure" => Ok(Self::Azure),
+            "gcs" => Ok(Self::Gcs),
+            "local" => Ok(Self::Local),
+            _ => Err(format!(
+                "Unknown storage backend {value}, expected s3, azure, gcs, or local."
+            )),
+        }
+    }
+}
+
+/// Configuration of the object store used to measure the size of the tables. For the cloud
+/// backends, `options` are passed to the builder as configuration keys, e.g.,
+/// `aws_secret_access_key`, while the local backend requires a `path` option with the root
+/// directory that contains a directory per bucket.
+#[derive(Clone, Deserialize)]
+pub(super) struct StorageConfig {
+    pub(super) backend: StorageBackend,
+    #[serde(default)]
+    pub(super) options: HashMap<String, String>,
+    /// If the cloud backends first read their configuration from their standard environment
+    /// variables, e.g., `AWS_ACCESS_KEY_ID` for S3, before `options` are applied. Otherwise, only
+    /// `options` are used.
+    #[serde(default)]
+    pub(super) from_env: bool,
+}
+
+impl StorageConfig {
+    /// Read the storage configuration from the file in [`STORAGE_FILE_ENV`] if it is set, otherwise
+    /// from [`STORAGE_BACKEND_ENV`] and the backend's standard environment variables. If neither is
+    /// set, the MinIO instance in `docker-compose-cluster.yml` is used.
+    pub(super) fn try_from_env() -> Result<Self, String> {
+        if let Ok(path) = env::var(STORAGE_FILE_ENV) {
+            let json = std::fs::read_to_string(&path)
+                .map_err(|error| format!("Could not read {path}: {error}"))?;
+
+            return serde_json::from_str(&json)
+                .map_err(|error| format!("Could not parse {path}: {error}"));
+        }
+
+        match env::var(STORAGE_BACKEND_ENV) {
+            Ok(backend) => {
+                let backend = backend.parse()?;
+
+                let mut options = HashMap::new();
+                if let Ok(path) = env::var(STORAGE_PATH_ENV) {
+                    options.insert("path".to_owned(), path);
+                }
+
+                Ok(Self {
+                    backend,
+                    options,
+                    from_env: true,
+                })
+            }
+            Err(_) => Ok(Self::minio()),
+        }
+    }
+
+    /// Configuration of the MinIO instance in `docker-compose-cluster.yml`.
+    fn minio() -> Self {
+        let options = HashMap::from([
+            ("aws_access_key_id".to_owned(), "minioadmin".to_owned()),
+            ("aws_secret_access_key".to_owned(), "minioadmin".to_owned()),
+            (
+                "aws_endpoint_url".to_owned(),
+                "http://127.0.0.1:9000".to_owned(),
+            ),
+            ("aws_allow_http".to_owned(), "true".to_owned()),
+        ]);
+
+        Self {
+            backend: StorageBackend::S3,
+            options,
+            from_env: false,
+        }
+    }
+
+    /// Build an object store for `bucket_name` using the configured backend. Options that are not
+    /// valid configuration keys for the backend are ignored, and the environment variables of the
+    /// backend are only read if `from_env` is set.
+    pub(super) fn build_object_store(
+        &self,
+        bucket_name: &str,
+    ) -> Result<Arc<dyn ObjectStore>, String> {
+        let object_store: Arc<dyn ObjectStore> = match self.backend {
+            StorageBackend::S3 => {
+                let builder = if self.from_env {
+                    AmazonS3Builder::from_env()
+                } else {
+                    AmazonS3Builder::new()
+                };
+
+                Arc::new(
+                    self.options
+                        .iter()
+                        .fold(
+                            builder.with_bucket_name(bucket_name),
+                            |builder, (key, value)| match key.parse() {
+                                Ok(k) => builder.with_config(k, value),
+                                Err(_) => builder,
+                            },
+                        )
+                        .build()
+                        .map_err(|error| error.to_string())?,
+                )
+            }
+            StorageBackend::Azure => {
+                let builder = if self.from_env {
+                    MicrosoftAzureBuilder::from_env()
+                } else {
+                    MicrosoftAzureBuilder::new()
+                };
+
+                Arc::new(
+                    self.options
+                        .iter()
+                        .fold(
+                            builder.with_container_name(bucket_name),
+                            |builder, (key, value)| match key.parse() {
+                                Ok(k) => builder.with_config(k, value),
+                                Err(_) => builder,
+                            },
+                        )
+                        .build()
+                        .map_err(|error| error.to_string())?,
+                )
+            }
+            StorageBackend::Gcs => {
+                let builder = if self.from_env {
+                    GoogleCloudStorageBuilder::from_env()
+                } else {
+                    GoogleCloudStorageBuilder::new()
+                };
+
+                Arc::new(
+                    self.options
+                        .iter()
+                        .fold(
+                            builder.with_bucket_name(bucket_name),
+                            |builder, (key, value)| match key.parse() {
+                                Ok(k) => builder.with_config(k, value),
+                                Err(_) => builder,
+                            },
+                        )
+                        .build()
+                        .map_err(|error| error.to_string())?,
+                )
+            }
+            StorageBackend::Local => {
+                let root = self
+                    .options
+                    .get("path")
+                    .ok_or_else(|| "The local storage backend requires a path.".to_owned())?;
+
+                let bucket_path = PathBuf::from(root).join(bucket_name);
+                std::fs::create_dir_all(&bucket_path).map_err(|error| error.to_string())?;
+
+                Arc::new(
+                    LocalFileSystem::new_with_prefix(bucket_path)
+                        .map_err(|error| error.to_string())?,
+                )
+            }
+        };
+
+        Ok(object_store)
+    }
+}
@@ -0,0 +1,599 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime::{self, JoinHandle};
+use tokio::time;
+
+use crate::error::DemoError;
+use crate::events::{self, EventEmitter};
+
+/// How long a task must run before failing for its earlier failures to no longer count towards the
+/// restarts in a row.
+const STABLE_RUN_PERIOD: Duration = Duration::from_secs(60);
+
+/// Creates a new instance of a task each time it is started, restarted, or resumed.
+type TaskFactory = Arc<dyn Fn() -> BoxFuture<'static, Result<(), DemoError>> + Send + Sync>;
+
+/// The state of a supervised task.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "status", content = "reason", rename_all = "lowercase")]
+pub(super) enum TaskState {
+    Running,
+    Stopped,
+    /// The task has finished its work, so it is not resumed with the stopped tasks.
+    Completed,
+    Failed(String),
+}
+
+/// What to do when a supervised task fails, either by returning an error or by panicking.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum RestartPolicy {
+    /// Leave the task in the failed state.
+    Never,
+    /// Restart the task after `delay_ms` milliseconds, at most `max_restarts` times in a row. The
+    /// count starts over when the task has run for [`STABLE_RUN_PERIOD`] before failing.
+    OnFailure { max_restarts: u32, delay_ms: u64 },
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self::OnFailure {
+            max_restarts: 5,
+            delay_ms: 1000,
+        }
+    }
+}
+
+/// The state of a supervised task as returned by `list_tasks` and emitted in `task-status` events.
+#[derive(Clone, Serialize)]
+pub(super) struct TaskInfo {
+    name: String,
+    state: TaskState,
+    restarts: u32,
+    restart_policy: RestartPolicy,
+}
+
+struct SupervisedTask {
+    factory: TaskFactory,
+    state: TaskState,
+    restart_policy: RestartPolicy,
+    restarts: u32,
+    /// Incremented each time the task is started or stopped, so a runner that has been replaced
+    /// does not update the state of the task.
+    generation: u64,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl SupervisedTask {
+    fn info(&self, name: &str) -> TaskInfo {
+        TaskInfo {
+            name: name.to_owned(),
+            state: self.state.clone(),
+            restarts: self.restarts,
+            restart_policy: self.restart_policy,
+        }
+    }
+
+    /// Update the state of the task after it has finished, either successfully or with the error
+    /// `reason`, and return how long to wait before restarting it. Returns [`None`] if the task
+    /// should not be restarted.
+    fn finish(&mut self, reason: Option<String>, ran_stably: bool) -> Option<Duration> {
+        match reason {
+            None => {
+                self.state = TaskState::Completed;
+                self.handle = None;
+                None
+            }
+            Some(reason) => {
+                self.state = TaskState::Failed(reason);
+
+                // The task recovered from its earlier failures, so they no longer count.
+                if ran_stably {
+                    self.restarts = 0;
+                }
+
+                match self.restart_policy {
+                    RestartPolicy::OnFailure {
+                        max_restarts,
+                        delay_ms,
+                    } if self.restarts < max_restarts => {
+                        self.restarts += 1;
+                        Some(Duration::from_millis(delay_ms))
+                    }
+                    _ => {
+                        self.handle = None;
+                        None
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Runs named background tasks, keeps track of their state, and restarts them according to their
+/// [`RestartPolicy`] if they fail. Each change of state is emitted as a `task-status` event.
+#[derive(Clone)]
+pub(super) struct Supervisor<E: EventEmitter> {
+    emitter: E,
+    tasks: Arc<Mutex<BTreeMap<String, SupervisedTask>>>,
+}
+
+impl<E: EventEmitter> Supervisor<E> {
+    pub(super) fn new(emitter: E) -> Self {
+        Self {
+            emitter,
+            tasks: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Start the task with `name` using `factory` to create it. If a task with `name` is already
+    /// supervised, it is stopped and replaced while its restart policy is kept.
+    pub(super) fn start<F, Fut>(&self, name: &str, factory: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), DemoError>> + Send + 'static,
+    {
+        self.start_task(name, None, factory);
+    }
+
+    /// Start the task with `name` using `factory` to create it like [`Self::start`], but with
+    /// `restart_policy` from the beginning instead of the restart policy of the task it replaces.
+    pub(super) fn start_with_policy<F, Fut>(
+        &self,
+        name: &str,
+        restart_policy: RestartPolicy,
+        factory: F,
+    ) where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), DemoError>> + Send + 'static,
+    {
+        self.start_task(name, Some(restart_policy), factory);
+    }
+
+    fn start_task<F, Fut>(&self, name: &str, restart_policy: Option<RestartPolicy>, factory: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), DemoError>> + Send + 'static,
+    {
+        let factory: TaskFactory = Arc::new(move || factory().boxed());
+        let mut tasks = self.tasks.lock().unwrap();
+
+        let (restart_policy, generation) = match tasks.get(name) {
+            Some(task) => {
+                if let Some(handle) = &task.handle {
+                    handle.abort();
+                }
+
+                (
+                    restart_policy.unwrap_or(task.restart_policy),
+                    task.generation,
+                )
+            }
+            None => (restart_policy.unwrap_or_default(), 0),
+        };
+
+        tasks.insert(
+            name.to_owned(),
+            SupervisedTask {
+                factory,
+                state: TaskState::Stopped,
+                restart_policy,
+                restarts: 0,
+                generation,
+                handle: None,
+            },
+        );
+
+        self.spawn(&mut tasks, name);
+    }
+
+    /// Stop the task with `name` without removing it, so it can be resumed later.
+    pub(super) fn pause(&self, name: &str) -> Result<(), DemoError> {
+        let mut tasks = self.tasks.lock().unwrap();
+        let task = tasks
+            .get_mut(name)
+            .ok_or_else(|| DemoError::Configuration(format!("No task named {name} exists.")))?;
+
+        if let Some(handle) = task.handle.take() {
+            handle.abort();
+        }
+
+        task.generation += 1;
+
+        if task.state == TaskState::Running {
+            task.state = TaskState::Stopped;
+            self.emitter.emit_event("task-status", task.info(name));
+        }
+
+        Ok(())
+    }
+
+    /// Start the task with `name` again if it is stopped or has failed. A task that has completed
+    /// is only started again by [`Self::start`].
+    pub(super) fn resume(&self, name: &str) -> Result<(), DemoError> {
+        let mut tasks = self.tasks.lock().unwrap();
+        let task = tasks
+            .get_mut(name)
+            .ok_or_else(|| DemoError::Configuration(format!("No task named {name} exists.")))?;
+
+        if task.handle.is_none() && task.state != TaskState::Completed {
+            task.restarts = 0;
+            self.spawn(&mut tasks, name);
+        }
+
+        Ok(())
+    }
+
+    /// Stop the tasks in `names` that are supervised.
+    pub(super) fn pause_all(&self, names: &[&str]) {
+        for name in names {
+            // Tasks that have not been started yet cannot be paused.
+            let _ = self.pause(name);
+        }
+    }
+
+    /// Start the tasks in `names` that are supervised again if they are stopped or have failed.
+    pub(super) fn resume_all(&self, names: &[&str]) {
+        for name in names {
+            // Tasks that have not been started yet cannot be resumed.
+            let _ = self.resume(name);
+        }
+    }
+
+    /// Set the restart policy of the task with `name`. It is used the next time the task fails.
+    pub(super) fn set_restart_policy(
+        &self,
+        name: &str,
+        restart_policy: RestartPolicy,
+    ) -> Result<(), DemoError> {
+        let mut tasks = self.tasks.lock().unwrap();
+        let task = tasks
+            .get_mut(name)
+            .ok_or_else(|| DemoError::Configuration(format!("No task named {name} exists.")))?;
+
+        task.restart_policy = restart_policy;
+        self.emitter.emit_event("task-status", task.info(name));
+
+        Ok(())
+    }
+
+    /// Return the state of each supervised task ordered by name.
+    pub(super) fn list(&self) -> Vec<TaskInfo> {
+        self.tasks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, task)| task.info(name))
+            .collect()
+    }
+
+    fn spawn(&self, tasks: &mut BTreeMap<String, SupervisedTask>, name: &str) {
+        let task = tasks.get_mut(name).unwrap();
+
+        task.generation += 1;
+        task.state = TaskState::Running;
+        task.handle = Some(async_runtime::spawn(run_supervised(
+            self.clone(),
+            name.to_owned(),
+            task.generation,
+        )));
+
+        self.emitter.emit_event("task-status", task.info(name));
+    }
+
+    /// Update the task with `name` using `update` if it has not been replaced or stopped since
+    /// `generation` was started. Returns [`None`] if it has.
+    fn update_current<T>(
+        &self,
+        name: &str,
+        generation: u64,
+        update: impl FnOnce(&mut SupervisedTask) -> T,
+    ) -> Option<T> {
+        let mut tasks = self.tasks.lock().unwrap();
+
+        let task = tasks
+            .get_mut(name)
+            .filter(|task| task.generation == generation)?;
+
+        let state = task.state.clone();
+        let result = update(task);
+
+        if task.state != state {
+            self.emitter.emit_event("task-status", task.info(name));
+        }
+
+        Some(result)
+    }
+}
+
+/// Run the task with `name` until it completes, is stopped, or fails more times in a row than its
+/// restart policy allows.
+async fn run_supervised<E: EventEmitter>(supervisor: Supervisor<E>, name: String, generation: u64) {
+    loop {
+        let Some(factory) = supervisor.update_current(&name, generation, |task| {
+            task.state = TaskState::Running;
+            task.factory.clone()
+        }) else {
+            return;
+        };
+
+        let started = Instant::now();
+        let reason = match AssertUnwindSafe(factory()).catch_unwind().await {
+            Ok(Ok(())) => None,
+            Ok(Err(error)) => {
+                let reason = error.to_string();
+                events::emit_task_error(&supervisor.emitter, &name, error);
+                Some(reason)
+            }
+            Err(_panic) => Some("The task panicked.".to_owned()),
+        };
+        let ran_stably = started.elapsed() >= STABLE_RUN_PERIOD;
+
+        let restart_delay =
+            supervisor.update_current(&name, generation, |task| task.finish(reason, ran_stably));
+
+        match restart_delay {
+            Some(Some(delay)) => time::sleep(delay).await,
+            _ => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::future;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const TASK: &str = "task";
+
+    /// Records the emitted events, so the state transitions of the tasks can be checked.
+    #[derive(Clone, Default)]
+    struct StubEmitter {
+        events: Arc<Mutex<Vec<(String, serde_json::Value)>>>,
+    }
+
+    impl StubEmitter {
+        /// Return the status of each `task-status` event in the order they were emitted.
+        fn statuses(&self) -> Vec<String> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(event, _payload)| event == "task-status")
+                .map(|(_event, payload)| payload["state"]["status"].as_str().unwrap().to_owned())
+                .collect()
+        }
+    }
+
+    impl EventEmitter for StubEmitter {
+        fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+            let payload = serde_json::to_value(payload).unwrap();
+            self.events
+                .lock()
+                .unwrap()
+                .push((event.to_owned(), payload));
+        }
+    }
+
+    #[test]
+    fn test_finish_completes_task() {
+        let mut task = supervised_task(RestartPolicy::default());
+
+        assert_eq!(task.finish(None, false), None);
+        assert_eq!(task.state, TaskState::Completed);
+    }
+
+    #[test]
+    fn test_finish_restarts_task_at_most_max_restarts_times() {
+        let mut task = supervised_task(RestartPolicy::OnFailure {
+            max_restarts: 2,
+            delay_ms: 100,
+        });
+
+        for restarts in 1..=2 {
+            assert_eq!(
+                task.finish(Some("Failed.".to_owned()), false),
+                Some(Duration::from_millis(100))
+            );
+            assert_eq!(task.restarts, restarts);
+        }
+
+        assert_eq!(task.finish(Some("Failed.".to_owned()), false), None);
+        assert_eq!(task.state, TaskState::Failed("Failed.".to_owned()));
+        assert_eq!(task.restarts, 2);
+    }
+
+    #[test]
+    fn test_finish_resets_restarts_after_stable_run() {
+        let mut task = supervised_task(RestartPolicy::OnFailure {
+            max_restarts: 2,
+            delay_ms: 100,
+        });
+        task.restarts = 2;
+
+        assert_eq!(
+            task.finish(Some("Failed.".to_owned()), true),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(task.restarts, 1);
+    }
+
+    #[test]
+    fn test_finish_never_restarts_task() {
+        let mut task = supervised_task(RestartPolicy::Never);
+
+        assert_eq!(task.finish(Some("Failed.".to_owned()), false), None);
+        assert_eq!(task.restarts, 0);
+    }
+
+    fn supervised_task(restart_policy: RestartPolicy) -> SupervisedTask {
+        SupervisedTask {
+            factory: Arc::new(complete),
+            state: TaskState::Running,
+            restart_policy,
+            restarts: 0,
+            generation: 1,
+            handle: None,
+        }
+    }
+
+    #[test]
+    fn test_failing_task_is_restarted_max_restarts_times() {
+        let supervisor = Supervisor::new(StubEmitter::default());
+        let restart_policy = RestartPolicy::OnFailure {
+            max_restarts: 2,
+            delay_ms: 0,
+        };
+        let calls = start_counted(&supervisor, restart_policy, fail);
+
+        // The task is not restarted after the last failure, so it has failed once for each start.
+        async_runtime::block_on(wait_for(&supervisor, |_task| {
+            supervisor.emitter.statuses().len() == 6
+        }));
+
+        let task = &supervisor.list()[0];
+        assert_eq!(
+            task.state,
+            TaskState::Failed("Configuration error: Failed.".to_owned())
+        );
+        assert_eq!(task.restarts, 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            supervisor.emitter.statuses(),
+            ["running", "failed", "running", "failed", "running", "failed"]
+        );
+    }
+
+    #[test]
+    fn test_pause_during_restart_delay_stops_restart() {
+        let supervisor = Supervisor::new(StubEmitter::default());
+        let restart_policy = RestartPolicy::OnFailure {
+            max_restarts: 1,
+            delay_ms: 100,
+        };
+        let calls = start_counted(&supervisor, restart_policy, fail);
+
+        async_runtime::block_on(async {
+            wait_for(&supervisor, |task| {
+                matches!(task.state, TaskState::Failed(_))
+            })
+            .await;
+            supervisor.pause(TASK).unwrap();
+
+            time::sleep(Duration::from_millis(300)).await;
+        });
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(supervisor.emitter.statuses(), ["running", "failed"]);
+
+        supervisor.resume(TASK).unwrap();
+        async_runtime::block_on(wait_for(&supervisor, |_task| {
+            calls.load(Ordering::SeqCst) == 2
+        }));
+    }
+
+    #[test]
+    fn test_pause_and_resume_running_task() {
+        let supervisor = Supervisor::new(StubEmitter::default());
+        let calls = start_counted(&supervisor, RestartPolicy::default(), run_forever);
+
+        async_runtime::block_on(wait_for(&supervisor, |_task| {
+            calls.load(Ordering::SeqCst) == 1
+        }));
+
+        supervisor.pause(TASK).unwrap();
+        assert_eq!(supervisor.list()[0].state, TaskState::Stopped);
+
+        supervisor.resume(TASK).unwrap();
+        async_runtime::block_on(wait_for(&supervisor, |_task| {
+            calls.load(Ordering::SeqCst) == 2
+        }));
+
+        assert_eq!(supervisor.list()[0].state, TaskState::Running);
+        assert_eq!(
+            supervisor.emitter.statuses(),
+            ["running", "stopped", "running"]
+        );
+    }
+
+    #[test]
+    fn test_completed_task_is_not_resumed() {
+        let supervisor = Supervisor::new(StubEmitter::default());
+        let calls = start_counted(&supervisor, RestartPolicy::default(), complete);
+
+        async_runtime::block_on(wait_for(&supervisor, |task| {
+            task.state == TaskState::Completed
+        }));
+
+        supervisor.resume(TASK).unwrap();
+        async_runtime::block_on(time::sleep(Duration::from_millis(100)));
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(supervisor.list()[0].state, TaskState::Completed);
+    }
+
+    #[test]
+    fn test_pause_and_resume_unknown_task() {
+        let supervisor = Supervisor::new(StubEmitter::default());
+
+        assert!(matches!(
+            supervisor.pause(TASK),
+            Err(DemoError::Configuration(_))
+        ));
+        assert!(matches!(
+            supervisor.resume(TASK),
+            Err(DemoError::Configuration(_))
+        ));
+    }
+
+    /// Start [`TASK`] with `restart_policy` and return the number of times `task` has been called.
+    fn start_counted(
+        supervisor: &Supervisor<StubEmitter>,
+        restart_policy: RestartPolicy,
+        task: fn() -> BoxFuture<'static, Result<(), DemoError>>,
+    ) -> Arc<AtomicU32> {
+        let calls = Arc::new(AtomicU32::new(0));
+
+        let factory_calls = calls.clone();
+        supervisor.start_with_policy(TASK, restart_policy, move || {
+            factory_calls.fetch_add(1, Ordering::SeqCst);
+            task()
+        });
+
+        calls
+    }
+
+    /// Wait until the state of [`TASK`] satisfies `predicate`.
+    async fn wait_for(supervisor: &Supervisor<StubEmitter>, predicate: impl Fn(&TaskInfo) -> bool) {
+        for _ in 0..500 {
+            if predicate(&supervisor.list()[0]) {
+                return;
+            }
+
+            time::sleep(Duration::from_millis(10)).await;
+        }
+
+        panic!("The task did not reach the expected state.");
+    }
+
+    fn complete() -> BoxFuture<'static, Result<(), DemoError>> {
+        future::ready(Ok(())).boxed()
+    }
+
+    fn fail() -> BoxFuture<'static, Result<(), DemoError>> {
+        future::ready(Err(DemoError::Configuration("Failed.".to_owned()))).boxed()
+    }
+
+    fn run_forever() -> BoxFuture<'static, Result<(), DemoError>> {
+        future::pending().boxed()
+    }
+}
@@ -0,0 +1,541 @@
+use std::path::Path;
+
+use modelardb_embedded::operations::client::Node;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// The system a node in the cluster is running.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum NodeType {
+    Modelardb,
+    Comparison,
+}
+
+/// The role a node has in the cluster.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum ServerMode {
+    Local,
+    Manager,
+    Edge,
+    Cloud,
+}
+
+/// A single entry in the nodes file, e.g., `data/nodes.json`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(super) struct NodeConfig {
+    #[serde(rename = "type")]
+    pub(super) node_type: NodeType,
+    pub(super) url: Option<String>,
+    pub(super) server_mode: ServerMode,
+    pub(super) latitude: Option<f64>,
+    pub(super) longitude: Option<f64>,
+}
+
+/// The nodes in the cluster that the demo connects to, built from a nodes file.
+#[derive(Clone)]
+pub(super) struct Topology {
+    /// All entries in the nodes file, including the local node without a URL.
+    pub(super) nodes: Vec<NodeConfig>,
+    /// URL of the ModelarDB manager used to create and drop tables.
+    pub(super) manager_url: String,
+    /// Pairs of ModelarDB and comparison edge nodes from the nodes file or added at runtime.
+    pub(super) configured_edge_nodes: Vec<(Node, Node)>,
+    /// URLs of the ModelarDB edge nodes in `configured_edge_nodes` that were added at runtime.
+    pub(super) added_edge_urls: Vec<String>,
+    /// Pairs of ModelarDB and comparison edge nodes that currently receive the same data. Once the
+    /// ModelarDB manager has been asked for its registered nodes, only the pairs with a registered
+    /// ModelarDB node and the pairs added at runtime are included.
+    pub(super) edge_nodes: Vec<(Node, Node)>,
+    /// URLs of the ModelarDB and comparison cloud nodes.
+    pub(super) cloud_urls: Vec<String>,
+}
+
+/// A node registered with the ModelarDB manager.
+#[derive(Clone, Debug)]
+pub(super) struct RegisteredNode {
+    pub(super) url: String,
+    pub(super) server_mode: ServerMode,
+}
+
+/// The nodes that are currently part of the cluster, as emitted to the frontend.
+#[derive(Clone, Serialize)]
+pub(super) struct ClusterTopology {
+    manager_url: String,
+    edge_nodes: Vec<EdgeNodePair>,
+    cloud_urls: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct EdgeNodePair {
+    modelardb_url: String,
+    comparison_url: String,
+}
+
+impl Topology {
+    /// Read the nodes file at `path` and build the topology from it.
+    pub(super) fn try_from_file(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|error| format!("Could not read {}: {error}", path.display()))?;
+
+        let nodes: Vec<NodeConfig> = serde_json::from_str(&json)
+            .map_err(|error| format!("Could not parse {}: {error}", path.display()))?;
+
+        Self::try_from_nodes(nodes)
+    }
+
+    /// Build the topology from `nodes`. The ModelarDB and comparison edge nodes are paired in the
+    /// order they are listed in, so the number of edge nodes of each type must match.
+    pub(super) fn try_from_nodes(nodes: Vec<NodeConfig>) -> Result<Self, String> {
+        let urls_with =
+            |node_type: NodeType, server_mode: ServerMode| -> Result<Vec<String>, String> {
+                nodes
+                    .iter()
+                    .filter(|node| node.node_type == node_type && node.server_mode == server_mode)
+                    .map(|node| {
+                        node.url.clone().ok_or_else(|| {
+                            format!("The {node_type:?} {server_mode:?} node is missing a URL.")
+                        })
+                    })
+                    .collect()
+            };
+
+        let mut manager_urls = urls_with(NodeType::Modelardb, ServerMode::Manager)?;
+        let manager_url = match manager_urls.len() {
+            1 => manager_urls.remove(0),
+            count => return Err(format!("Expected one manager node, found {count}.")),
+        };
+
+        let modelardb_edge_urls = urls_with(NodeType::Modelardb, ServerMode::Edge)?;
+        let comparison_edge_urls = urls_with(NodeType::Comparison, ServerMode::Edge)?;
+
+        if modelardb_edge_urls.is_empty() {
+            return Err("The topology must contain at least one edge node.".to_owned());
+        }
+
+        if modelardb_edge_urls.len() != comparison_edge_urls.len() {
+            return Err(format!(
+                "Each ModelarDB edge node must be paired with a comparison edge node, found {} \
+                 ModelarDB and {} comparison edge nodes.",
+                modelardb_edge_urls.len(),
+                comparison_edge_urls.len()
+            ));
+        }
+
+        let edge_nodes: Vec<(Node, Node)> = modelardb_edge_urls
+            .into_iter()
+            .zip(comparison_edge_urls)
+            .map(|(modelardb_url, comparison_url)| {
+                (Node::Server(modelardb_url), Node::Server(comparison_url))
+            })
+            .collect();
+
+        let mut cloud_urls = urls_with(NodeType::Modelardb, ServerMode::Cloud)?;
+        cloud_urls.extend(urls_with(NodeType::Comparison, ServerMode::Cloud)?);
+
+        Ok(Self {
+            nodes,
+            manager_url,
+            configured_edge_nodes: edge_nodes.clone(),
+            added_edge_urls: vec![],
+            edge_nodes,
+            cloud_urls,
+        })
+    }
+
+    /// Add a new pair of ModelarDB and comparison edge nodes to the topology. The demo does not
+    /// start the nodes, so they may not be registered with the ModelarDB manager. The pair is
+    /// therefore kept when the registered nodes are applied until it is removed, while the health
+    /// monitor stops the ingestion into it if it does not respond.
+    pub(super) fn add_edge_node(
+        &mut self,
+        modelardb_url: String,
+        comparison_url: String,
+    ) -> Result<(), String> {
+        let already_added =
+            self.configured_edge_nodes
+                .iter()
+                .any(|(modelardb_node, comparison_node)| {
+                    modelardb_node.url() == modelardb_url || comparison_node.url() == comparison_url
+                });
+
+        if already_added {
+            return Err(format!(
+                "An edge node with the URL {modelardb_url} or {comparison_url} already exists."
+            ));
+        }
+
+        for (node_type, url) in [
+            (NodeType::Modelardb, &modelardb_url),
+            (NodeType::Comparison, &comparison_url),
+        ] {
+            self.nodes.push(NodeConfig {
+                node_type,
+                url: Some(url.clone()),
+                server_mode: ServerMode::Edge,
+                latitude: None,
+                longitude: None,
+            });
+        }
+
+        self.added_edge_urls.push(modelardb_url.clone());
+
+        let edge_node = (Node::Server(modelardb_url), Node::Server(comparison_url));
+        self.configured_edge_nodes.push(edge_node.clone());
+        self.edge_nodes.push(edge_node);
+
+        Ok(())
+    }
+
+    /// Remove the ModelarDB edge node with `modelardb_url` and the comparison edge node it is
+    /// paired with from the topology. The last pair of edge nodes cannot be removed.
+    pub(super) fn remove_edge_node(&mut self, modelardb_url: &str) -> Result<(), String> {
+        let index = self
+            .configured_edge_nodes
+            .iter()
+            .position(|(modelardb_node, _comparison_node)| modelardb_node.url() == modelardb_url)
+            .ok_or_else(|| format!("No edge node with the URL {modelardb_url} exists."))?;
+
+        if self.configured_edge_nodes.len() == 1 {
+            return Err("The last edge node cannot be removed.".to_owned());
+        }
+
+        let (modelardb_node, comparison_node) = self.configured_edge_nodes.remove(index);
+        self.added_edge_urls.retain(|url| url != modelardb_url);
+
+        self.edge_nodes
+            .retain(|(edge_node, _comparison_node)| edge_node.url() != modelardb_url);
+
+        self.nodes.retain(|node| {
+            let url = node.url.as_deref();
+            url != Some(modelardb_node.url()) && url != Some(comparison_node.url())
+        });
+
+        Ok(())
+    }
+
+    /// Update the edge and cloud nodes that are part of the cluster to the nodes in
+    /// `registered_nodes`. The nodes register with the manager using the address they are reachable
+    /// at from inside Docker, so they are matched with the configured nodes by port. Registered
+    /// edge nodes without a configured comparison node are ignored as they cannot receive the same
+    /// data, while the edge nodes added at runtime are kept whether they are registered or not.
+    /// Returns [`true`] if the topology changed.
+    pub(super) fn apply_registered_nodes(&mut self, registered_nodes: &[RegisteredNode]) -> bool {
+        let registered_ports = |server_mode: ServerMode| -> Vec<u16> {
+            registered_nodes
+                .iter()
+                .filter(|node| node.server_mode == server_mode)
+                .filter_map(|node| url_port(&node.url))
+                .collect()
+        };
+
+        let registered_edge_ports = registered_ports(ServerMode::Edge);
+        let edge_nodes: Vec<(Node, Node)> = self
+            .configured_edge_nodes
+            .iter()
+            .filter(|(modelardb_node, _comparison_node)| {
+                self.added_edge_urls
+                    .iter()
+                    .any(|url| url == modelardb_node.url())
+                    || url_port(modelardb_node.url())
+                        .is_some_and(|port| registered_edge_ports.contains(&port))
+            })
+            .cloned()
+            .collect();
+
+        // Use the configured URL for registered cloud nodes as it is reachable from the host.
+        let configured_cloud_urls: Vec<&String> = self
+            .nodes
+            .iter()
+            .filter(|node| {
+                node.node_type == NodeType::Modelardb && node.server_mode == ServerMode::Cloud
+            })
+            .filter_map(|node| node.url.as_ref())
+            .collect();
+
+        let mut cloud_urls: Vec<String> = registered_nodes
+            .iter()
+            .filter(|node| node.server_mode == ServerMode::Cloud)
+            .map(|node| {
+                configured_cloud_urls
+                    .iter()
+                    .find(|url| url_port(url).is_some() && url_port(url) == url_port(&node.url))
+                    .map(|url| url.to_string())
+                    .unwrap_or_else(|| node.url.clone())
+            })
+            .collect();
+
+        cloud_urls.extend(
+            self.nodes
+                .iter()
+                .filter(|node| {
+                    node.node_type == NodeType::Comparison && node.server_mode == ServerMode::Cloud
+                })
+                .filter_map(|node| node.url.clone()),
+        );
+
+        let edge_node_urls = |edge_nodes: &[(Node, Node)]| -> Vec<String> {
+            edge_nodes
+                .iter()
+                .map(|(modelardb_node, _comparison_node)| modelardb_node.url().to_owned())
+                .collect()
+        };
+
+        let changed = edge_node_urls(&edge_nodes) != edge_node_urls(&self.edge_nodes)
+            || cloud_urls != self.cloud_urls;
+
+        self.edge_nodes = edge_nodes;
+        self.cloud_urls = cloud_urls;
+
+        changed
+    }
+
+    /// Return the nodes that are currently part of the cluster in the format emitted to the frontend.
+    pub(super) fn cluster_topology(&self) -> ClusterTopology {
+        ClusterTopology {
+            manager_url: self.manager_url.clone(),
+            edge_nodes: self
+                .edge_nodes
+                .iter()
+                .map(|(modelardb_node, comparison_node)| EdgeNodePair {
+                    modelardb_url: modelardb_node.url().to_owned(),
+                    comparison_url: comparison_node.url().to_owned(),
+                })
+                .collect(),
+            cloud_urls: self.cloud_urls.clone(),
+        }
+    }
+
+    /// Return the names of the Docker volumes used by the edge nodes, e.g., `modelardb-9981`, as
+    /// they are named in `docker-compose-cluster.yml`.
+    pub(super) fn edge_volume_names(&self) -> Vec<String> {
+        let volume_name =
+            |prefix: &str, node: &Node| url_port(node.url()).map(|port| format!("{prefix}-{port}"));
+
+        self.edge_nodes
+            .iter()
+            .flat_map(|(modelardb_node, comparison_node)| {
+                [
+                    volume_name("modelardb", modelardb_node),
+                    volume_name("comparison", comparison_node),
+                ]
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Return [`true`] if `url` is the URL of a cloud node in the topology.
+    pub(super) fn is_cloud_url(&self, url: &str) -> bool {
+        self.cloud_urls.iter().any(|cloud_url| cloud_url == url)
+    }
+}
+
+/// Return the port in `url` if it has one.
+fn url_port(url: &str) -> Option<u16> {
+    Url::parse(url).ok().and_then(|url| url.port())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(node_type: NodeType, server_mode: ServerMode, url: Option<&str>) -> NodeConfig {
+        NodeConfig {
+            node_type,
+            url: url.map(str::to_owned),
+            server_mode,
+            latitude: None,
+            longitude: None,
+        }
+    }
+
+    fn nodes() -> Vec<NodeConfig> {
+        vec![
+            node(NodeType::Modelardb, ServerMode::Local, None),
+            node(
+                NodeType::Modelardb,
+                ServerMode::Manager,
+                Some("grpc://127.0.0.1:9998"),
+            ),
+            node(
+                NodeType::Modelardb,
+                ServerMode::Edge,
+                Some("grpc://127.0.0.1:9981"),
+            ),
+            node(
+                NodeType::Comparison,
+                ServerMode::Edge,
+                Some("grpc://127.0.0.1:9881"),
+            ),
+            node(
+                NodeType::Modelardb,
+                ServerMode::Edge,
+                Some("grpc://127.0.0.1:9982"),
+            ),
+            node(
+                NodeType::Comparison,
+                ServerMode::Edge,
+                Some("grpc://127.0.0.1:9882"),
+            ),
+            node(
+                NodeType::Comparison,
+                ServerMode::Cloud,
+                Some("grpc://127.0.0.1:9890"),
+            ),
+            node(
+                NodeType::Modelardb,
+                ServerMode::Cloud,
+                Some("grpc://127.0.0.1:9990"),
+            ),
+        ]
+    }
+
+    fn edge_node_urls(edge_nodes: &[(Node, Node)]) -> Vec<(&str, &str)> {
+        edge_nodes
+            .iter()
+            .map(|(modelardb_node, comparison_node)| (modelardb_node.url(), comparison_node.url()))
+            .collect()
+    }
+
+    fn registered_node(url: &str, server_mode: ServerMode) -> RegisteredNode {
+        RegisteredNode {
+            url: url.to_owned(),
+            server_mode,
+        }
+    }
+
+    #[test]
+    fn test_try_from_nodes_pairs_edge_nodes_in_order() {
+        let topology = Topology::try_from_nodes(nodes()).unwrap();
+
+        assert_eq!(topology.manager_url, "grpc://127.0.0.1:9998");
+        assert_eq!(
+            edge_node_urls(&topology.edge_nodes),
+            [
+                ("grpc://127.0.0.1:9981", "grpc://127.0.0.1:9881"),
+                ("grpc://127.0.0.1:9982", "grpc://127.0.0.1:9882")
+            ]
+        );
+        assert_eq!(
+            edge_node_urls(&topology.configured_edge_nodes),
+            edge_node_urls(&topology.edge_nodes)
+        );
+        assert_eq!(
+            topology.cloud_urls,
+            ["grpc://127.0.0.1:9990", "grpc://127.0.0.1:9890"]
+        );
+    }
+
+    #[test]
+    fn test_try_from_nodes_requires_one_manager() {
+        let mut nodes = nodes();
+        nodes.retain(|node| node.server_mode != ServerMode::Manager);
+        assert!(Topology::try_from_nodes(nodes.clone()).is_err());
+
+        nodes.push(node(
+            NodeType::Modelardb,
+            ServerMode::Manager,
+            Some("grpc://127.0.0.1:9998"),
+        ));
+        nodes.push(node(
+            NodeType::Modelardb,
+            ServerMode::Manager,
+            Some("grpc://127.0.0.1:9999"),
+        ));
+        assert!(Topology::try_from_nodes(nodes).is_err());
+    }
+
+    #[test]
+    fn test_try_from_nodes_requires_urls() {
+        let mut nodes = nodes();
+        nodes[2].url = None;
+
+        assert!(Topology::try_from_nodes(nodes).is_err());
+    }
+
+    #[test]
+    fn test_try_from_nodes_requires_paired_edge_nodes() {
+        let mut nodes = nodes();
+        nodes.remove(5);
+        assert!(Topology::try_from_nodes(nodes.clone()).is_err());
+
+        nodes.retain(|node| node.server_mode != ServerMode::Edge);
+        assert!(Topology::try_from_nodes(nodes).is_err());
+    }
+
+    #[test]
+    fn test_apply_registered_nodes_matches_nodes_by_port() {
+        let mut topology = Topology::try_from_nodes(nodes()).unwrap();
+
+        // The nodes register with the addresses they are reachable at from inside Docker.
+        let registered_nodes = [
+            registered_node("grpc://modelardb-edge-2:9982", ServerMode::Edge),
+            registered_node("grpc://modelardb-edge-3:9983", ServerMode::Edge),
+            registered_node("grpc://modelardb-cloud:9990", ServerMode::Cloud),
+        ];
+
+        assert!(topology.apply_registered_nodes(&registered_nodes));
+        assert_eq!(
+            edge_node_urls(&topology.edge_nodes),
+            [("grpc://127.0.0.1:9982", "grpc://127.0.0.1:9882")]
+        );
+        assert_eq!(topology.configured_edge_nodes.len(), 2);
+        assert_eq!(
+            topology.cloud_urls,
+            ["grpc://127.0.0.1:9990", "grpc://127.0.0.1:9890"]
+        );
+
+        assert!(!topology.apply_registered_nodes(&registered_nodes));
+    }
+
+    #[test]
+    fn test_apply_registered_nodes_keeps_unconfigured_cloud_url() {
+        let mut topology = Topology::try_from_nodes(nodes()).unwrap();
+
+        let registered_nodes = [
+            registered_node("grpc://modelardb-edge-1:9981", ServerMode::Edge),
+            registered_node("grpc://modelardb-edge-2:9982", ServerMode::Edge),
+            registered_node("grpc://modelardb-cloud:9991", ServerMode::Cloud),
+        ];
+
+        assert!(topology.apply_registered_nodes(&registered_nodes));
+        assert_eq!(topology.edge_nodes.len(), 2);
+        assert_eq!(
+            topology.cloud_urls,
+            ["grpc://modelardb-cloud:9991", "grpc://127.0.0.1:9890"]
+        );
+    }
+
+    #[test]
+    fn test_apply_registered_nodes_keeps_edge_nodes_added_at_runtime() {
+        let mut topology = Topology::try_from_nodes(nodes()).unwrap();
+        topology
+            .add_edge_node(
+                "grpc://127.0.0.1:9983".to_owned(),
+                "grpc://127.0.0.1:9883".to_owned(),
+            )
+            .unwrap();
+
+        let registered_nodes = [
+            registered_node("grpc://modelardb-edge-1:9981", ServerMode::Edge),
+            registered_node("grpc://modelardb-cloud:9990", ServerMode::Cloud),
+        ];
+
+        assert!(topology.apply_registered_nodes(&registered_nodes));
+        assert_eq!(
+            edge_node_urls(&topology.edge_nodes),
+            [
+                ("grpc://127.0.0.1:9981", "grpc://127.0.0.1:9881"),
+                ("grpc://127.0.0.1:9983", "grpc://127.0.0.1:9883")
+            ]
+        );
+
+        topology.remove_edge_node("grpc://127.0.0.1:9983").unwrap();
+        assert!(topology.added_edge_urls.is_empty());
+        assert_eq!(
+            edge_node_urls(&topology.edge_nodes),
+            [("grpc://127.0.0.1:9981", "grpc://127.0.0.1:9881")]
+        );
+    }
+}
@@ -1,126 +1,98 @@
-use std::collections::HashMap;
+use std::io::Cursor;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use arrow::datatypes::{ArrowPrimitiveType, DataType, Field, Schema};
+use arrow::datatypes::SchemaRef;
+use arrow::ipc::reader::StreamReader;
 use arrow::ipc::writer::{IpcWriteOptions, StreamWriter};
 use arrow::record_batch::RecordBatch;
-use arrow_flight::flight_service_client::FlightServiceClient;
-use futures_util::StreamExt;
-use modelardb_embedded::operations::client::{Client, Node};
-use modelardb_types::types::{ArrowTimestamp, ArrowValue};
-use object_store::aws::{AmazonS3, AmazonS3Builder};
+use futures_util::TryStreamExt;
 use object_store::path::Path;
 use object_store::ObjectStore;
-use tonic::transport::Channel;
-use url::Url;
-
-pub(super) fn build_s3_object_store(bucket_name: String) -> AmazonS3 {
-    let location = format!("s3://{bucket_name}");
-
-    let storage_options = HashMap::from([
-        ("aws_access_key_id".to_owned(), "minioadmin".to_owned()),
-        ("aws_secret_access_key".to_owned(), "minioadmin".to_owned()),
-        (
-            "aws_endpoint_url".to_owned(),
-            "http://127.0.0.1:9000".to_owned(),
-        ),
-        ("aws_bucket_name".to_owned(), bucket_name),
-        ("aws_s3_allow_unsafe_rename".to_owned(), "true".to_owned()),
-    ]);
-
-    let url = Url::parse(&location).unwrap();
-
-    // Build the Amazon S3 object store with the given storage options manually to allow http.
-    storage_options
-        .iter()
-        .fold(
-            AmazonS3Builder::new()
-                .with_url(url.to_string())
-                .with_allow_http(true),
-            |builder, (key, value)| match key.parse() {
-                Ok(k) => builder.with_config(k, value),
-                Err(_) => builder,
-            },
-        )
-        .build()
-        .unwrap()
-}
 
-pub(super) fn table_schema() -> Schema {
-    Schema::new(vec![
-        Field::new("timestamp", ArrowTimestamp::DATA_TYPE, false),
-        Field::new("park_id", DataType::Utf8, false),
-        Field::new("windmill_id", DataType::Utf8, false),
-        Field::new("wind_speed", ArrowValue::DATA_TYPE, false),
-        Field::new("pitch_angle", ArrowValue::DATA_TYPE, false),
-        Field::new("rotor_speed", ArrowValue::DATA_TYPE, false),
-        Field::new("active_power", ArrowValue::DATA_TYPE, false),
-        Field::new("cos_nacelle_dir", ArrowValue::DATA_TYPE, false),
-        Field::new("sin_nacelle_dir", ArrowValue::DATA_TYPE, false),
-        Field::new("cos_wind_dir", ArrowValue::DATA_TYPE, false),
-        Field::new("sin_wind_dir", ArrowValue::DATA_TYPE, false),
-        Field::new("cor_nacelle_direction", ArrowValue::DATA_TYPE, false),
-        Field::new("cor_wind_direction", ArrowValue::DATA_TYPE, false),
-    ])
-}
+use crate::error::DemoError;
 
-pub(super) async fn tables_size(object_store: &AmazonS3) -> u64 {
+pub(super) async fn tables_size(object_store: &dyn ObjectStore) -> Result<u64, DemoError> {
     let tables_path = Path::from("tables".to_owned());
     let table_files = object_store
         .list(Some(&tables_path))
-        .collect::<Vec<_>>()
-        .await;
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    Ok(table_files.into_iter().map(|file| file.size).sum::<u64>())
+}
+
+/// Convert a [`RecordBatch`] to a [`Vec<u8>`].
+pub(super) fn try_convert_record_batch_to_bytes(
+    record_batch: &RecordBatch,
+) -> Result<Vec<u8>, DemoError> {
+    let options = IpcWriteOptions::default();
+    let mut writer = StreamWriter::try_new_with_options(vec![], &record_batch.schema(), options)?;
 
-    table_files
-        .into_iter()
-        .map(|file| file.unwrap().size)
-        .sum::<u64>()
+    writer.write(record_batch)?;
+    Ok(writer.into_inner()?)
 }
 
-pub(super) fn edge_nodes() -> Vec<(Node, Node)> {
-    vec![
-        (
-            Node::Server("grpc://127.0.0.1:9981".to_owned()),
-            Node::Server("http://127.0.0.1:9881".to_owned()),
-        ),
-        (
-            Node::Server("grpc://127.0.0.1:9982".to_owned()),
-            Node::Server("http://127.0.0.1:9882".to_owned()),
-        ),
-        (
-            Node::Server("grpc://127.0.0.1:9983".to_owned()),
-            Node::Server("http://127.0.0.1:9883".to_owned()),
-        ),
-        (
-            Node::Server("grpc://127.0.0.1:9984".to_owned()),
-            Node::Server("http://127.0.0.1:9884".to_owned()),
-        ),
-    ]
+/// Convert the bytes of an Arrow IPC stream to the [`RecordBatches`](RecordBatch) in it.
+pub(super) fn try_convert_bytes_to_record_batches(
+    bytes: &[u8],
+) -> Result<Vec<RecordBatch>, DemoError> {
+    let reader = StreamReader::try_new(Cursor::new(bytes), None)?;
+
+    Ok(reader.collect::<Result<Vec<_>, _>>()?)
 }
 
-pub(super) async fn connect_to_nodes(
-    nodes: Vec<(Node, Node)>,
-) -> Vec<(Client, FlightServiceClient<Channel>)> {
-    let mut clients = vec![];
+/// Read the schema of the Arrow IPC stream in `bytes`.
+pub(super) fn try_convert_bytes_to_schema(bytes: &[u8]) -> Result<SchemaRef, DemoError> {
+    let reader = StreamReader::try_new(Cursor::new(bytes), None)?;
 
-    for (modelardb_node, comparison_node) in nodes {
-        let modelardb_client = Client::connect(modelardb_node).await.unwrap();
+    Ok(reader.schema())
+}
 
-        let comparison_client = FlightServiceClient::connect(comparison_node.url().to_owned())
-            .await
-            .unwrap();
+/// A small xorshift generator, so the rate profiles and synthetic data do not require a dependency
+/// on a random number crate.
+pub(super) struct Random(u64);
 
-        clients.push((modelardb_client, comparison_client));
+impl Random {
+    fn new(seed: u64) -> Self {
+        // Xorshift never leaves the zero state.
+        Self(seed.max(1))
     }
 
-    clients
-}
+    /// Create a generator from `seed`, or from the current time if no seed is given, so the
+    /// numbers are only reproducible when a seed is given.
+    pub(super) fn from_seed(seed: Option<u64>) -> Self {
+        Self::new(seed_or_now(seed))
+    }
 
-/// Convert a [`RecordBatch`] to a [`Vec<u8>`].
-pub(super) fn try_convert_record_batch_to_bytes(record_batch: &RecordBatch) -> Vec<u8> {
-    let options = IpcWriteOptions::default();
-    let mut writer =
-        StreamWriter::try_new_with_options(vec![], &record_batch.schema(), options).unwrap();
+    /// Create a generator like [`Self::from_seed`] with its own sequence of random numbers for the
+    /// edge node at `node_index`. The offset from the seed is a multiple of `stream`, so different
+    /// uses of the same seed can be given different sequences.
+    pub(super) fn for_node(seed: Option<u64>, node_index: usize, stream: u64) -> Self {
+        Self::new(seed_or_now(seed).wrapping_add((node_index as u64 + 1) * stream))
+    }
+
+    /// Return a number between 0 and 1.
+    pub(super) fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+
+        (self.0 >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    /// Return a number from the standard normal distribution using the Box-Muller transform.
+    pub(super) fn next_gaussian(&mut self) -> f64 {
+        let uniform = 1.0 - self.next_f64();
+        (-2.0 * uniform.ln()).sqrt() * (2.0 * std::f64::consts::PI * self.next_f64()).cos()
+    }
+}
 
-    writer.write(record_batch).unwrap();
-    writer.into_inner().unwrap()
+/// Return `seed`, or the current time in nanoseconds if no seed is given.
+fn seed_or_now(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64
+    })
 }
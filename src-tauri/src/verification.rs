@@ -0,0 +1,228 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use arrow::array::{AsArray, RecordBatch};
+use arrow::compute;
+use arrow::datatypes::{DataType, Int64Type};
+use datafusion::parquet::arrow::async_reader::{
+    ParquetObjectReader, ParquetRecordBatchStreamBuilder,
+};
+use datafusion::physical_plan::common;
+use futures_util::TryStreamExt;
+use modelardb_embedded::operations::client::{Client, Node};
+use modelardb_embedded::operations::Operations;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use serde::Serialize;
+
+use crate::disorder::BatchKind;
+use crate::error::DemoError;
+
+/// The rows that were written to the ModelarDB and comparison edge nodes since the ingestion was
+/// started.
+#[derive(Clone, Debug, Default, Serialize)]
+pub(super) struct WrittenRows {
+    pub(super) modelardb: RowCounts,
+    pub(super) comparison: RowCounts,
+}
+
+/// The number of rows written to a system, including how many of them were in duplicate or late
+/// batches.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub(super) struct RowCounts {
+    rows: usize,
+    duplicate_rows: usize,
+    late_rows: usize,
+}
+
+impl RowCounts {
+    /// Count `rows` rows written in a batch of `kind`.
+    pub(super) fn add(&mut self, kind: BatchKind, rows: usize) {
+        self.rows += rows;
+
+        match kind {
+            BatchKind::Regular => (),
+            BatchKind::Duplicate => self.duplicate_rows += rows,
+            BatchKind::Late => self.late_rows += rows,
+        }
+    }
+}
+
+/// The rows stored in a system as returned when they are read from it.
+#[derive(Clone, Debug, Default, Serialize)]
+struct StoredRows {
+    rows: usize,
+    /// The number of rows with a distinct turbine and timestamp.
+    distinct_rows: usize,
+    duplicate_rows: usize,
+    /// The number of rows with an earlier timestamp than the previous row from the same turbine.
+    out_of_order_rows: usize,
+    first_timestamp: Option<i64>,
+    last_timestamp: Option<i64>,
+    /// Files that could not be read, e.g., Apache ORC files.
+    unreadable_files: usize,
+}
+
+/// Computes [`StoredRows`] from the batches read from a system in the order they are returned.
+#[derive(Default)]
+struct StoredRowsBuilder {
+    stored_rows: StoredRows,
+    keys: HashSet<(String, String, i64)>,
+    last_timestamps: HashMap<(String, String), i64>,
+}
+
+impl StoredRowsBuilder {
+    /// Add the rows in `record_batch`, which has the park ID, windmill ID, and timestamp columns.
+    fn push(&mut self, record_batch: &RecordBatch) -> Result<(), DemoError> {
+        let park_ids = compute::cast(record_batch.column(0), &DataType::Utf8)?;
+        let windmill_ids = compute::cast(record_batch.column(1), &DataType::Utf8)?;
+        let timestamps = compute::cast(record_batch.column(2), &DataType::Int64)?;
+
+        let park_ids = park_ids.as_string::<i32>();
+        let windmill_ids = windmill_ids.as_string::<i32>();
+        let timestamps = timestamps.as_primitive::<Int64Type>();
+
+        let stored_rows = &mut self.stored_rows;
+        for index in 0..record_batch.num_rows() {
+            let turbine = (
+                park_ids.value(index).to_owned(),
+                windmill_ids.value(index).to_owned(),
+            );
+            let timestamp = timestamps.value(index);
+
+            stored_rows.rows += 1;
+            stored_rows.first_timestamp = Some(
+                stored_rows
+                    .first_timestamp
+                    .map_or(timestamp, |first| first.min(timestamp)),
+            );
+            stored_rows.last_timestamp = Some(
+                stored_rows
+                    .last_timestamp
+                    .map_or(timestamp, |last| last.max(timestamp)),
+            );
+
+            if let Some(last_timestamp) = self.last_timestamps.insert(turbine.clone(), timestamp) {
+                if timestamp < last_timestamp {
+                    stored_rows.out_of_order_rows += 1;
+                }
+            }
+
+            if !self.keys.insert((turbine.0, turbine.1, timestamp)) {
+                stored_rows.duplicate_rows += 1;
+            }
+        }
+
+        stored_rows.distinct_rows = self.keys.len();
+
+        Ok(())
+    }
+}
+
+/// How the rows written to a system compare to the rows it stores.
+#[derive(Clone, Debug, Serialize)]
+struct SystemReport {
+    written: RowCounts,
+    stored: StoredRows,
+}
+
+/// How ModelarDB and the comparison format store and return out-of-order, duplicate, and late rows.
+#[derive(Clone, Debug, Serialize)]
+pub(super) struct VerificationReport {
+    modelardb: SystemReport,
+    comparison: SystemReport,
+}
+
+/// Compare `written_rows` to the rows in the table with `table_name` read from the ModelarDB cloud
+/// node with `cloud_url` and from the Apache Parquet files flushed to
+/// `comparison_remote_object_store`. Only rows that have been flushed are included.
+pub(super) async fn try_verify(
+    written_rows: WrittenRows,
+    table_name: &str,
+    timestamp_column: &str,
+    cloud_url: &str,
+    comparison_remote_object_store: Arc<dyn ObjectStore>,
+) -> Result<VerificationReport, DemoError> {
+    let mut modelardb_client = Client::connect(Node::Server(cloud_url.to_owned()))
+        .await
+        .map_err(|error| DemoError::Connection(error.to_string()))?;
+
+    let query = format!("SELECT park_id, windmill_id, \"{timestamp_column}\" FROM {table_name}");
+
+    let record_batch_stream = modelardb_client
+        .read(&query)
+        .await
+        .map_err(|error| DemoError::FlightStatus(error.to_string()))?;
+
+    let record_batches = common::collect(record_batch_stream)
+        .await
+        .map_err(|error| DemoError::FlightStatus(error.to_string()))?;
+
+    let mut modelardb_rows = StoredRowsBuilder::default();
+    for record_batch in &record_batches {
+        modelardb_rows.push(record_batch)?;
+    }
+
+    let comparison_rows =
+        try_read_comparison_rows(comparison_remote_object_store, table_name, timestamp_column)
+            .await?;
+
+    Ok(VerificationReport {
+        modelardb: SystemReport {
+            written: written_rows.modelardb,
+            stored: modelardb_rows.stored_rows,
+        },
+        comparison: SystemReport {
+            written: written_rows.comparison,
+            stored: comparison_rows,
+        },
+    })
+}
+
+/// Read the rows in the files flushed by the comparison nodes to the directory of the table with
+/// `table_name` in `object_store` in the order the files are listed in.
+async fn try_read_comparison_rows(
+    object_store: Arc<dyn ObjectStore>,
+    table_name: &str,
+    timestamp_column: &str,
+) -> Result<StoredRows, DemoError> {
+    let table_path = Path::from(format!("tables/{table_name}"));
+    let files = object_store
+        .list(Some(&table_path))
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let mut stored_rows = StoredRowsBuilder::default();
+    let mut unreadable_files = 0;
+
+    for file in files {
+        if file.location.extension() != Some("parquet") {
+            unreadable_files += 1;
+            continue;
+        }
+
+        let reader =
+            ParquetObjectReader::new(object_store.clone(), file.location).with_file_size(file.size);
+        let record_batches: Vec<RecordBatch> = ParquetRecordBatchStreamBuilder::new(reader)
+            .await?
+            .build()?
+            .try_collect()
+            .await?;
+
+        for record_batch in record_batches {
+            let schema = record_batch.schema();
+            let projection = [
+                schema.index_of("park_id")?,
+                schema.index_of("windmill_id")?,
+                schema.index_of(timestamp_column)?,
+            ];
+
+            stored_rows.push(&record_batch.project(&projection)?)?;
+        }
+    }
+
+    let mut stored_rows = stored_rows.stored_rows;
+    stored_rows.unreadable_files = unreadable_files;
+
+    Ok(stored_rows)
+}
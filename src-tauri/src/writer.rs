@@ -0,0 +1,335 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use arrow::array::RecordBatch;
+use arrow_flight::flight_service_client::FlightServiceClient;
+use modelardb_embedded::operations::client::Client;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tonic::transport::Channel;
+
+use crate::disorder::BatchKind;
+use crate::events::{self, EventEmitter};
+use crate::faults::FaultInjector;
+use crate::verification::WrittenRows;
+
+/// What happens to a new batch for an edge node that already has the maximum number of batches in
+/// flight.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum BackpressurePolicy {
+    /// Wait until a batch has been written, which also delays the ingestion into the other edge
+    /// nodes.
+    #[default]
+    Block,
+    /// Drop the oldest batch that is waiting to be written, or the new batch if none are waiting.
+    DropOldest,
+    /// Drop the new batch.
+    DropNewest,
+}
+
+/// How many batches may be in flight to each edge node and what happens when the limit is reached.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub(super) struct Backpressure {
+    /// The maximum number of batches that are waiting to be written or being written to an edge
+    /// node.
+    pub(super) max_in_flight_batches: usize,
+    pub(super) policy: BackpressurePolicy,
+}
+
+impl Default for Backpressure {
+    fn default() -> Self {
+        Self {
+            max_in_flight_batches: 4,
+            policy: BackpressurePolicy::Block,
+        }
+    }
+}
+
+/// The file format the comparison nodes store the batches in.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum ComparisonFormat {
+    Parquet,
+    Orc,
+}
+
+impl ComparisonFormat {
+    /// The action that makes a comparison node store a batch in the format.
+    pub(super) fn ingest_action(&self) -> &'static str {
+        match self {
+            Self::Parquet => "IngestDataParquet",
+            Self::Orc => "IngestDataOrc",
+        }
+    }
+}
+
+/// How the batches are written, shared by the writers of all of the edge nodes.
+#[derive(Clone)]
+pub(super) struct WriteOptions {
+    pub(super) comparison: ComparisonFormat,
+    pub(super) fault_injector: Arc<FaultInjector>,
+    /// The rows that have been written to the nodes, which are verified against the stored rows.
+    pub(super) written_rows: Arc<Mutex<WrittenRows>>,
+    /// The object stores the ModelarDB and comparison nodes flush to, which are measured after the
+    /// final flush when the ingestion completes.
+    pub(super) modelardb_remote_object_store: Arc<dyn ObjectStore>,
+    pub(super) comparison_remote_object_store: Arc<dyn ObjectStore>,
+}
+
+/// A batch and the clients for the pair of edge nodes it is written to.
+pub(super) struct WriteRequest {
+    pub(super) modelardb_client: Client,
+    pub(super) comparison_client: FlightServiceClient<Channel>,
+    pub(super) record_batch: RecordBatch,
+    pub(super) kind: BatchKind,
+}
+
+/// The batches in flight to an edge node.
+struct Queue {
+    requests: VecDeque<WriteRequest>,
+    /// If the worker is writing a batch that has been removed from `requests`.
+    writing: bool,
+    /// If no more batches are added, so the worker stops when `requests` is empty.
+    closed: bool,
+    dropped_batches: u64,
+}
+
+impl Queue {
+    fn in_flight(&self) -> usize {
+        self.requests.len() + usize::from(self.writing)
+    }
+}
+
+/// The queue shared between a [`NodeWriter`] and its worker. `changed` is notified each time a
+/// batch is added to or removed from the queue.
+struct SharedQueue {
+    queue: Mutex<Queue>,
+    changed: Notify,
+}
+
+#[derive(Clone, Serialize)]
+struct QueueDepth {
+    url: String,
+    depth: usize,
+}
+
+#[derive(Clone, Serialize)]
+struct BatchDropped {
+    url: String,
+    rows: usize,
+    dropped_batches: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct WriteLatency {
+    url: String,
+    rows: usize,
+    latency_ms: u128,
+}
+
+/// Writes the batches ingested into an edge node in order using a worker, with a bounded number of
+/// batches in flight. The worker writes the remaining batches and stops when the writer is dropped.
+pub(super) struct NodeWriter<E: EventEmitter> {
+    emitter: E,
+    /// URL of the ModelarDB edge node, used to identify the edge node in the events.
+    url: String,
+    backpressure: Backpressure,
+    shared_queue: Arc<SharedQueue>,
+}
+
+impl<E: EventEmitter> NodeWriter<E> {
+    /// Create a writer for the ModelarDB edge node with `url` and the comparison node with
+    /// `comparison_url` and start its worker.
+    pub(super) fn new(
+        emitter: E,
+        url: String,
+        comparison_url: String,
+        backpressure: Backpressure,
+        write_options: WriteOptions,
+    ) -> Self {
+        let shared_queue = Arc::new(SharedQueue {
+            queue: Mutex::new(Queue {
+                requests: VecDeque::new(),
+                writing: false,
+                closed: false,
+                dropped_batches: 0,
+            }),
+            changed: Notify::new(),
+        });
+
+        tokio::spawn(write_requests(
+            emitter.clone(),
+            url.clone(),
+            comparison_url,
+            shared_queue.clone(),
+            write_options,
+        ));
+
+        Self {
+            emitter,
+            url,
+            backpressure,
+            shared_queue,
+        }
+    }
+
+    /// Add `request` to the batches in flight to the edge node. If the maximum number of batches
+    /// are already in flight, `request` is handled according to the backpressure policy.
+    pub(super) async fn write(&self, request: WriteRequest) {
+        let max_in_flight_batches = self.backpressure.max_in_flight_batches.max(1);
+        let mut request = Some(request);
+
+        loop {
+            // Created before the queue is checked so a change after the check is not missed.
+            let changed = self.shared_queue.changed.notified();
+
+            let (depth, dropped) = {
+                let mut queue = self.shared_queue.queue.lock().unwrap();
+
+                if queue.in_flight() < max_in_flight_batches {
+                    queue.requests.extend(request.take());
+                    (queue.in_flight(), None)
+                } else {
+                    match self.backpressure.policy {
+                        BackpressurePolicy::Block => (queue.in_flight(), None),
+                        BackpressurePolicy::DropOldest => {
+                            let dropped = match queue.requests.pop_front() {
+                                Some(dropped) => {
+                                    queue.requests.extend(request.take());
+                                    dropped
+                                }
+                                None => request.take().unwrap(),
+                            };
+
+                            queue.dropped_batches += 1;
+                            (queue.in_flight(), Some((dropped, queue.dropped_batches)))
+                        }
+                        BackpressurePolicy::DropNewest => {
+                            queue.dropped_batches += 1;
+                            let dropped = request.take().unwrap();
+                            (queue.in_flight(), Some((dropped, queue.dropped_batches)))
+                        }
+                    }
+                }
+            };
+
+            if let Some((dropped, dropped_batches)) = dropped {
+                self.emitter.emit_event(
+                    "batch-dropped",
+                    BatchDropped {
+                        url: self.url.clone(),
+                        rows: dropped.record_batch.num_rows(),
+                        dropped_batches,
+                    },
+                );
+            }
+
+            if request.is_none() {
+                self.shared_queue.changed.notify_waiters();
+                emit_queue_depth(&self.emitter, &self.url, depth);
+                return;
+            }
+
+            changed.await;
+        }
+    }
+
+    /// Wait until all of the batches in flight to the edge node have been written.
+    pub(super) async fn written(&self) {
+        loop {
+            let changed = self.shared_queue.changed.notified();
+
+            if self.shared_queue.queue.lock().unwrap().in_flight() == 0 {
+                return;
+            }
+
+            changed.await;
+        }
+    }
+}
+
+impl<E: EventEmitter> Drop for NodeWriter<E> {
+    fn drop(&mut self) {
+        self.shared_queue.queue.lock().unwrap().closed = true;
+        self.shared_queue.changed.notify_waiters();
+    }
+}
+
+/// Write the batches in `shared_queue` to the ModelarDB edge node with `url` and the comparison
+/// node with `comparison_url` in the order they were added until the queue is closed and empty. A
+/// `write-latency` event is emitted for each written batch.
+async fn write_requests<E: EventEmitter>(
+    emitter: E,
+    url: String,
+    comparison_url: String,
+    shared_queue: Arc<SharedQueue>,
+    write_options: WriteOptions,
+) {
+    loop {
+        let changed = shared_queue.changed.notified();
+
+        let request = {
+            let mut queue = shared_queue.queue.lock().unwrap();
+            let request = queue.requests.pop_front();
+
+            if request.is_none() && queue.closed {
+                return;
+            }
+
+            queue.writing = request.is_some();
+            request
+        };
+
+        let Some(request) = request else {
+            changed.await;
+            continue;
+        };
+
+        let rows = request.record_batch.num_rows();
+        let start = Instant::now();
+
+        let result = crate::ingest_data_points_into_nodes(
+            emitter.clone(),
+            &write_options,
+            &url,
+            &comparison_url,
+            request,
+        )
+        .await;
+
+        match result {
+            Ok(()) => emitter.emit_event(
+                "write-latency",
+                WriteLatency {
+                    url: url.clone(),
+                    rows,
+                    latency_ms: start.elapsed().as_millis(),
+                },
+            ),
+            Err(error) => events::emit_task_error(&emitter, crate::INGESTION_TASK, error),
+        }
+
+        let depth = {
+            let mut queue = shared_queue.queue.lock().unwrap();
+            queue.writing = false;
+            queue.in_flight()
+        };
+
+        shared_queue.changed.notify_waiters();
+        emit_queue_depth(&emitter, &url, depth);
+    }
+}
+
+fn emit_queue_depth<E: EventEmitter>(emitter: &E, url: &str, depth: usize) {
+    emitter.emit_event(
+        "write-queue-depth",
+        QueueDepth {
+            url: url.to_owned(),
+            depth,
+        },
+    );
+}
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { useDisclosure } from "@mantine/hooks";
 import {
   AppShell,
@@ -18,13 +19,14 @@ import { ModelardbNode } from "./interfaces/node.ts";
 import { CompressionRatio } from "./components/CompressionRatio/CompressionRatio.tsx";
 import { ConfigurationModal } from "./components/ConfigurationModal/ConfigurationModal.tsx";
 import { ComparisonSystem } from "./interfaces/system.ts";
-import { resolveResource } from "@tauri-apps/api/path";
-import { readTextFile } from "@tauri-apps/plugin-fs";
+import { ErrorBoundType, TaskError } from "./interfaces/event.ts";
+import { DemoError } from "./interfaces/error.ts";
 import "@mantine/core/styles.css";
 import "./App.css";
 
 export default function App() {
   const [resetKey, setResetKey] = useState(0);
+  const [paused, setPaused] = useState(false);
   const [
     configurationModalOpened,
     { open: openConfigurationModal, close: closeConfigurationModal },
@@ -37,6 +39,8 @@ export default function App() {
   const [comparisonSystemBytes, setComparisonSystemBytes] = useState(0);
 
   const [errorBound, setErrorBound] = useState(5);
+  const [errorBoundType, setErrorBoundType] =
+    useState<ErrorBoundType>("absolute");
   const [samplingRate, setSamplingRate] = useState(4000);
   const [comparisonSystem, setComparisonSystem] = useState<ComparisonSystem>({
     value: "parquet",
@@ -45,34 +49,54 @@ export default function App() {
 
   useEffect(() => {
     setTimeout(() => {
-      invoke("create_table", { errorBound: errorBound }).then(() => {
-        console.log("Table created successfully.");
+      // The cluster is already running after a reset, so the table is created even if it fails.
+      invoke("start_cluster")
+        .then(() => {
+          console.log("Cluster started successfully.");
+        })
+        .catch((error: DemoError) => {
+          console.error(`Could not start the cluster: ${error.message}`);
+        })
+        .then(() => {
+          invoke("create_table", {
+            errorBounds: {
+              default: { type: errorBoundType, value: errorBound },
+            },
+          }).then(() => {
+            console.log("Table created successfully.");
 
-        invoke("ingest_into_table", {
-          count: samplingRate,
-          comparison: comparisonSystem.value,
-        }).then(() => {
-          console.log(`Started ingesting data into the table.`);
+            invoke("ingest_into_table", {
+              count: samplingRate,
+              comparison: comparisonSystem.value,
+            }).then(() => {
+              console.log(`Started ingesting data into the table.`);
 
-          invoke("flush_nodes").then(() => {
-            console.log("Started flushing data from nodes.");
+              invoke("flush_nodes").then(() => {
+                console.log("Started flushing data from nodes.");
 
-            invoke("monitor_nodes", {
-              intervalSeconds: 1,
-            }).then(() => {
-              console.log("Started monitoring nodes.");
+                invoke("monitor_nodes", {
+                  intervalSeconds: 1,
+                }).then(() => {
+                  console.log("Started monitoring nodes.");
+                });
+              });
             });
           });
         });
-      });
     }, 2000);
   }, [resetKey]);
 
   useEffect(() => {
-    resolveResource("resources/nodes.json").then((resourcePath) => {
-      readTextFile(resourcePath).then((jsonData) => {
-        setNodes(JSON.parse(jsonData));
-      });
+    listen<TaskError>("task-error", (event) => {
+      console.error(
+        `The ${event.payload.task} task failed: ${event.payload.error.message}`
+      );
+    });
+  }, []);
+
+  useEffect(() => {
+    invoke<ModelardbNode[]>("cluster_nodes").then((clusterNodes) => {
+      setNodes(clusterNodes.filter((node) => node.server_mode !== "manager"));
     });
   }, []);
 
@@ -85,9 +109,17 @@ export default function App() {
     setModelarDbBytes(0);
     setComparisonSystemBytes(0);
 
+    setPaused(false);
     setResetKey((prev) => prev + 1);
   }
 
+  function handleTogglePause() {
+    invoke(paused ? "resume_demo" : "pause_demo").then(() => {
+      console.log(paused ? "Demo resumed." : "Demo paused.");
+      setPaused(!paused);
+    });
+  }
+
   return (
     <MantineProvider defaultColorScheme="dark" theme={theme}>
       <AppShell>
@@ -102,12 +134,16 @@ export default function App() {
             <ConfigurationModal
               errorBound={errorBound}
               setErrorBound={setErrorBound}
+              errorBoundType={errorBoundType}
+              setErrorBoundType={setErrorBoundType}
               samplingRate={samplingRate}
               setSamplingRate={setSamplingRate}
               comparisonSystem={comparisonSystem}
               setComparisonSystem={setComparisonSystem}
               close={closeConfigurationModal}
               handleReset={handleReset}
+              paused={paused}
+              handleTogglePause={handleTogglePause}
             />
           </Modal>
 
@@ -116,6 +152,7 @@ export default function App() {
               <Grid.Col span={7}>
                 <Configuration
                   errorBound={errorBound}
+                  errorBoundType={errorBoundType}
                   samplingRate={samplingRate}
                   comparisonSystem={comparisonSystem}
                 ></Configuration>
@@ -14,6 +14,7 @@ import { darcula } from "@uiw/codemirror-theme-darcula";
 import { useEffect, useState } from "react";
 import { IconBrandPython, IconPlayerPlayFilled } from "@tabler/icons-react";
 import { invoke } from "@tauri-apps/api/core";
+import { DemoError } from "../../interfaces/error.ts";
 
 const pythonScripts = [
   {
@@ -85,7 +86,9 @@ export function ClientModal() {
         output += `\n\nProcess finished with exit code ${exitCode}`;
         setConsoleOutput(output);
       }
-    );
+    ).catch((error: DemoError) => {
+      setConsoleOutput(error.message);
+    });
   }
 
   useEffect(() => {
@@ -1,15 +1,18 @@
 import { Container, Grid } from "@mantine/core";
 import { ConfigurationSetting } from "../ConfigurationSetting/ConfigurationSetting";
 import { ComparisonSystem } from "../../interfaces/system";
+import { ErrorBoundType } from "../../interfaces/event";
 
 type ConfigurationProps = {
   errorBound: number;
+  errorBoundType: ErrorBoundType;
   samplingRate: number;
   comparisonSystem: ComparisonSystem;
 };
 
 export function Configuration({
   errorBound,
+  errorBoundType,
   samplingRate,
   comparisonSystem,
 }: ConfigurationProps) {
@@ -19,7 +22,11 @@ export function Configuration({
         <Grid.Col span={6}>
           <ConfigurationSetting
             title="Compression"
-            value={`${errorBound}% Error Bound`}
+            value={
+              errorBoundType === "relative"
+                ? `${errorBound}% Error Bound`
+                : `${errorBound} Error Bound`
+            }
             type="ModelarDB"
           />
         </Grid.Col>
@@ -7,32 +7,42 @@ import {
 } from "@mantine/core";
 import { useForm } from "@mantine/form";
 import { ComparisonSystem } from "../../interfaces/system";
+import { ErrorBoundType } from "../../interfaces/event";
 
 type ConfigurationModalProps = {
   errorBound: number;
   setErrorBound: (value: number) => void;
+  errorBoundType: ErrorBoundType;
+  setErrorBoundType: (value: ErrorBoundType) => void;
   samplingRate: number;
   setSamplingRate: (value: number) => void;
   comparisonSystem: ComparisonSystem;
   setComparisonSystem: (value: ComparisonSystem) => void;
   close: () => void;
   handleReset: () => void;
+  paused: boolean;
+  handleTogglePause: () => void;
 };
 
 export function ConfigurationModal({
   errorBound,
   setErrorBound,
+  errorBoundType,
+  setErrorBoundType,
   samplingRate,
   setSamplingRate,
   comparisonSystem,
   setComparisonSystem,
   close,
   handleReset,
+  paused,
+  handleTogglePause,
 }: ConfigurationModalProps) {
   const form = useForm({
     mode: "uncontrolled",
     initialValues: {
       errorBound: errorBound,
+      errorBoundType: errorBoundType,
       samplingRate: samplingRate,
       comparisonSystem: comparisonSystem.value,
     },
@@ -40,6 +50,7 @@ export function ConfigurationModal({
 
   function handleSubmit(values: typeof form.values) {
     setErrorBound(values.errorBound);
+    setErrorBoundType(values.errorBoundType);
     setSamplingRate(values.samplingRate);
 
     setComparisonSystem({
@@ -73,14 +84,24 @@ export function ConfigurationModal({
           {...form.getInputProps("comparisonSystem")}
         />
 
+        <NativeSelect
+          mt={25}
+          label="ModelarDB Error Bound Type"
+          description="Whether the error bound is an absolute value or a percentage of the values"
+          multiple={false}
+          data={[
+            { value: "absolute", label: "Absolute" },
+            { value: "relative", label: "Relative (%)" },
+          ]}
+          key={form.key("errorBoundType")}
+          {...form.getInputProps("errorBoundType")}
+        />
+
         <NumberInput
           mt={25}
           label="ModelarDB Error Bound"
-          description="The relative error bound used to compress data in ModelarDB"
+          description="The error bound used to compress data in ModelarDB"
           min={0}
-          max={100}
-          suffix="%"
-          allowDecimal={false}
           key={form.key("errorBound")}
           {...form.getInputProps("errorBound")}
         />
@@ -99,6 +120,9 @@ export function ConfigurationModal({
         />
 
         <Group justify="flex-end" mt={40} me={20}>
+          <Button variant="default" onClick={handleTogglePause}>
+            {paused ? "Resume" : "Pause"}
+          </Button>
           <Button type="submit">Restart</Button>
         </Group>
       </form>
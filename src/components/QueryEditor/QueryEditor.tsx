@@ -5,6 +5,7 @@ import { darcula } from "@uiw/codemirror-theme-darcula";
 import { invoke } from "@tauri-apps/api/core";
 
 import { ModelardbNode } from "../../interfaces/node.ts";
+import { DemoError } from "../../interfaces/error.ts";
 import { IconPlayerPlayFilled } from "@tabler/icons-react";
 
 type QueryEditorProps = {
@@ -38,7 +39,9 @@ export function QueryEditor({
           setQueryData(json_data);
         }
       }
-    );
+    ).catch((error: DemoError) => {
+      setResultText(error.message);
+    });
   }
 
   return (
@@ -0,0 +1,11 @@
+export interface DemoError {
+  kind:
+    | "connection"
+    | "flight_status"
+    | "object_store"
+    | "schema"
+    | "script_runner"
+    | "docker"
+    | "configuration";
+  message: string;
+}
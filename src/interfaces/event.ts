@@ -1,9 +1,40 @@
+import { DemoError } from "./error";
+
 export interface RemoteObjectStoreTableSize {
   node_type: string;
   table_size: number;
 }
 
+export interface ColumnSize {
+  name: string;
+  arrow_size: number;
+  ipc_size: number;
+  csv_size: number;
+}
+
 export interface IngestedSize {
   table_name: string;
+  url: string;
   size: number;
+  arrow_size: number;
+  ipc_size: number;
+  csv_size: number;
+  columns: ColumnSize[];
+}
+
+export type ErrorBoundType = "absolute" | "relative";
+
+export interface ColumnErrorBound {
+  type: ErrorBoundType;
+  value: number;
+}
+
+export interface FieldErrorBound {
+  name: string;
+  error_bound: ColumnErrorBound;
+}
+
+export interface TaskError {
+  task: string;
+  error: DemoError;
 }
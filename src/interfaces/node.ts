@@ -1,7 +1,7 @@
 export interface ModelardbNode {
   type: "modelardb" | "comparison";
   url: string | undefined;
-  server_mode: "edge" | "cloud" | "local";
+  server_mode: "edge" | "cloud" | "local" | "manager";
   latitude: number;
   longitude: number;
 }